@@ -0,0 +1,50 @@
+#import bevy_pbr::mesh_view_bindings
+#import bevy_pbr::mesh_bindings
+#import bevy_pbr::mesh_functions
+#import warbler_grass::wind
+
+struct SwayingMaterial {
+    color: vec4<f32>,
+    // the height at which the object moves as much as the tip of a grass blade
+    sway_height: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> material: SwayingMaterial;
+@group(1) @binding(1)
+var wind_texture: texture_2d<f32>;
+@group(1) @binding(2)
+var wind_noise: texture_2d<f32>;
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_normal: vec3<f32>,
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    var world_position = mesh_position_local_to_world(mesh.model, vec4<f32>(vertex.position, 1.0));
+    // the object is anchored at its origin, like the root of a blade
+    let root = mesh_position_local_to_world(mesh.model, vec4<f32>(0., 0., 0., 1.0));
+    let height = max(world_position.y - root.y, 0.) / material.sway_height;
+    let grass_wind = load_grass_wind(wind_texture);
+    let offset = wind_offset(wind_noise, grass_wind.wind, grass_wind.noise_offset, root.xz);
+    world_position.x += offset.x * wind_strength(height);
+    world_position.z += offset.y * wind_strength(height);
+    out.clip_position = mesh_position_world_to_clip(world_position);
+    out.world_normal = mesh_normal_local_to_world(vertex.normal);
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // simple lighting from above, so the swaying is visible
+    let light = 0.5 + 0.5 * max(dot(normalize(in.world_normal), normalize(vec3<f32>(0.3, 1., 0.2))), 0.);
+    return vec4<f32>(material.color.rgb * light, material.color.a);
+}
@@ -0,0 +1,16 @@
+#import warbler_grass::grass_types
+
+struct ToonGrassMaterial {
+    shadow_color: vec4<f32>,
+    bands: f32,
+};
+
+@group(6) @binding(0)
+var<uniform> material: ToonGrassMaterial;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // the shading along the blade is split into a few flat bands
+    let band = floor(clamp(in.blade_height, 0., 0.999) * material.bands) / max(material.bands - 1., 1.);
+    return mix(material.shadow_color, in.color, band);
+}
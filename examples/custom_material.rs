@@ -0,0 +1,66 @@
+use bevy::{
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+use warbler_grass::{
+    material::{GrassMaterial, GrassMaterialPlugin},
+    warblers_plugin::WarblersPlugin,
+    WarblersBundle,
+};
+mod helper;
+
+/// Shades the blades in flat bands from the shadow color at the root to the grass color at the tip
+#[derive(AsBindGroup, TypeUuid, Clone)]
+#[uuid = "b6f0b4a3-3d7c-4f55-9a3e-0c1f5e2d8a41"]
+struct ToonGrassMaterial {
+    #[uniform(0)]
+    shadow_color: Color,
+    #[uniform(0)]
+    bands: f32,
+}
+
+impl GrassMaterial for ToonGrassMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/toon_grass.wgsl".into()
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(GrassMaterialPlugin::<ToonGrassMaterial>::default())
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .add_system(change_bands)
+        .run();
+}
+
+fn setup_grass(mut commands: Commands, mut materials: ResMut<Assets<ToonGrassMaterial>>) {
+    let material = materials.add(ToonGrassMaterial {
+        shadow_color: Color::rgb(0.05, 0.15, 0.1),
+        bands: 3.,
+    });
+    commands.spawn((
+        WarblersBundle {
+            grass_spawner: helper::get_grass_grid(),
+            ..default()
+        },
+        material,
+    ));
+}
+
+// the up and down arrow keys change the number of bands
+fn change_bands(input: Res<Input<KeyCode>>, mut materials: ResMut<Assets<ToonGrassMaterial>>) {
+    let change = if input.just_pressed(KeyCode::Up) {
+        1.
+    } else if input.just_pressed(KeyCode::Down) {
+        -1.
+    } else {
+        return;
+    };
+    for (_, material) in materials.iter_mut() {
+        material.bands = (material.bands + change).max(1.);
+    }
+}
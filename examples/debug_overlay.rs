@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use warbler_grass::{
+    debug::{GrassDebugSettings, WarblersDebugPlugin},
+    grass_spawner::GrassSpawner,
+    height_map::HeightMap,
+    prelude::*,
+};
+mod helper;
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(WarblersDebugPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .add_system(toggle_blades)
+        .run();
+}
+fn setup_grass(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // a chunk with explicit positions
+    commands.spawn(WarblersBundle {
+        grass_spawner: helper::get_grass_grid(),
+        ..default()
+    });
+    // a chunk using a height map, which is pending until the texture is loaded
+    let positions_xz = (0..2_500)
+        .map(|i| Vec2::new((i / 50) as f32, (i % 50) as f32) / 2.)
+        .collect();
+    commands.spawn(WarblersBundle {
+        grass_spawner: GrassSpawner::new()
+            .with_positions_xz(positions_xz)
+            .with_height_map(HeightMap::new(
+                asset_server.load("grass_height_map.png"),
+                3.,
+            )),
+        spatial: SpatialBundle {
+            transform: Transform::from_xyz(60., 0., 0.),
+            ..default()
+        },
+        ..default()
+    });
+}
+// press B to show a line for each blade
+fn toggle_blades(input: Res<Input<KeyCode>>, mut settings: ResMut<GrassDebugSettings>) {
+    if input.just_pressed(KeyCode::B) {
+        settings.show_blades = !settings.show_blades;
+    }
+}
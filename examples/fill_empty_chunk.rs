@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use warbler_grass::{
+    grass::GrassBlade, grass_spawner::GrassSpawner, warblers_plugin::WarblersPlugin, WarblersBundle,
+};
+mod helper;
+
+/// The next row of blades added to the chunk
+#[derive(Component, Default)]
+struct NextRow(u32);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .add_system(add_rows)
+        .run();
+}
+fn setup_grass(mut commands: Commands) {
+    // the chunk starts without any blades, so nothing is drawn
+    commands.spawn((
+        WarblersBundle {
+            grass_spawner: GrassSpawner::new(),
+            ..default()
+        },
+        NextRow::default(),
+    ));
+}
+// adds a row of blades to the chunk every tenth of a second, like a chunk which is streamed in
+fn add_rows(
+    time: Res<Time>,
+    mut timer: Local<Option<Timer>>,
+    mut chunks: Query<(&mut GrassSpawner, &mut NextRow)>,
+) {
+    let timer = timer.get_or_insert_with(|| Timer::from_seconds(0.1, TimerMode::Repeating));
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    for (mut spawner, mut next_row) in chunks.iter_mut() {
+        if next_row.0 >= 100 {
+            continue;
+        }
+        let x = next_row.0 as f32 / 2.;
+        spawner.append_blades((0..100).map(|z| GrassBlade {
+            position: Vec3::new(x, 0., z as f32 / 2.),
+            height: 1. + (x / 10.).sin() * 0.3,
+        }));
+        next_row.0 += 1;
+    }
+}
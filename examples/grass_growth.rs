@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use warbler_grass::{
+    growth::{GrassGrowth, GrowthEasing},
+    warblers_plugin::WarblersPlugin,
+    WarblersBundle,
+};
+mod helper;
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .add_system(replant)
+        .run();
+}
+fn setup_grass(mut commands: Commands) {
+    // each blade waits up to 3 seconds before it sprouts
+    let grass_spawner = helper::get_grass_grid().with_random_growth_delays(3., 7);
+    commands.spawn((
+        WarblersBundle {
+            grass_spawner,
+            ..default()
+        },
+        GrassGrowth::new(1.5)
+            .with_delay(1.)
+            .with_easing(GrowthEasing::Overshoot),
+    ));
+}
+
+// lets the grass grow again if the space key is pressed
+fn replant(input: Res<Input<KeyCode>>, mut growths: Query<&mut GrassGrowth>) {
+    if !input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for mut growth in growths.iter_mut() {
+        growth.elapsed = 0.;
+    }
+}
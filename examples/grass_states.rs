@@ -0,0 +1,88 @@
+use bevy::{math::Vec3Swizzles, prelude::*};
+use warbler_grass::prelude::*;
+mod helper;
+
+/// A ball of fire burning the grass it rolls over
+#[derive(Component)]
+struct Fire;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .add_system(move_fire)
+        .add_system(change_weather)
+        .run();
+}
+fn setup_grass(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((WarblersBundle {
+        grass_spawner: helper::get_grass_grid(),
+        ..default()
+    },));
+    // the state map covers the whole grass grid
+    let state_map =
+        GrassStateMap::new(&mut images, Vec2::ZERO, Vec2::splat(50.), UVec2::splat(256));
+    commands.insert_resource(state_map);
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(shape::UVSphere::default().into()),
+            material: materials.add(Color::ORANGE_RED.into()),
+            ..default()
+        },
+        Fire,
+    ));
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(25., 20., 25.),
+        ..default()
+    });
+}
+fn move_fire(
+    time: Res<Time>,
+    state_map: Res<GrassStateMap>,
+    mut images: ResMut<Assets<Image>>,
+    mut query: Query<&mut Transform, With<Fire>>,
+) {
+    let t = time.elapsed_seconds() / 4.;
+    for mut transform in &mut query {
+        transform.translation = Vec3::new(25. + t.cos() * 15., 1., 25. + (2. * t).sin() * 10.);
+        state_map.paint_circle(
+            &mut images,
+            GrassState::Burnt,
+            transform.translation.xz(),
+            1.5,
+            1.,
+        );
+    }
+}
+// F freezes the left half of the grass, R lets it rain in the center and C clears all states
+fn change_weather(
+    input: Res<Input<KeyCode>>,
+    state_map: Res<GrassStateMap>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if input.just_pressed(KeyCode::F) {
+        state_map.paint_rect(
+            &mut images,
+            GrassState::Frozen,
+            Vec2::ZERO,
+            Vec2::new(25., 50.),
+            1.,
+        );
+    }
+    if input.just_pressed(KeyCode::R) {
+        state_map.paint_circle(&mut images, GrassState::Wet, Vec2::splat(25.), 12., 1.);
+    }
+    if input.just_pressed(KeyCode::C) {
+        for state in [GrassState::Burnt, GrassState::Frozen, GrassState::Wet] {
+            state_map.clear(&mut images, state);
+        }
+    }
+}
@@ -12,7 +12,6 @@ fn main() {
 fn setup_grass(mut commands: Commands) {
     // we can define our blades how we want
     let (positions, heights) = (0..1000)
-        .into_iter()
         .map(|i| {
             let i = i as f32;
             (i.sin() * 20. / i.ln(), i.cos() * 20. / i.ln())
@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+use warbler_grass::{grass_asset::GrassChunkBundle, warblers_plugin::WarblersPlugin};
+mod helper;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(AssetPlugin {
+            // the grass is updated when meadow.grass or its height map changes on disk
+            watch_for_changes: true,
+            ..Default::default()
+        }))
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .run();
+}
+
+fn setup_grass(mut commands: Commands, asset_server: Res<AssetServer>) {
+    // the chunk was baked using `GrassChunkAsset::to_bytes`
+    commands.spawn(GrassChunkBundle {
+        grass_chunk: asset_server.load("meadow.grass"),
+        ..default()
+    });
+}
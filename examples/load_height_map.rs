@@ -1,6 +1,8 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, render::primitives::Aabb};
 use warbler_grass::{
-    grass_spawner::GrassSpawner, height_map::HeightMap, warblers_plugin::WarblersPlugin,
+    grass_spawner::GrassSpawner,
+    height_map::{HeightMap, HeightMapFilter},
+    warblers_plugin::WarblersPlugin,
     WarblersBundle,
 };
 mod helper;
@@ -14,20 +16,25 @@ fn main() {
         .add_plugin(WarblersPlugin)
         .add_plugin(helper::SimpleCamera)
         .add_startup_system(setup_grass)
+        .add_system(place_ball_on_ground)
         .run();
 }
-fn setup_grass(mut commands: Commands, asset_server: Res<AssetServer>) {
+#[derive(Component)]
+struct Ball;
+
+fn setup_grass(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
     let height_map = asset_server.load("grass_height_map.png");
 
     let positions_xz: Vec<Vec2> = (0..10_000)
-        .into_iter()
         .map(|i| (i / 100, i % 100))
         .map(|(x, z)| Vec2::new(x as f32, z as f32))
         .collect();
-    let height_map = HeightMap {
-        height_map,
-        height: 10.,
-    };
+    let height_map = HeightMap::new(height_map, 10.).with_filter(HeightMapFilter::Bilinear);
     let grass_spawner = GrassSpawner::new()
         .with_positions_xz(positions_xz)
         .with_height_map(height_map);
@@ -35,4 +42,34 @@ fn setup_grass(mut commands: Commands, asset_server: Res<AssetServer>) {
         grass_spawner,
         ..default()
     });
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(shape::UVSphere::default().into()),
+            material: materials.add(Color::WHITE.into()),
+            ..default()
+        },
+        Ball,
+    ));
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(50., 30., 50.),
+        ..default()
+    });
+}
+// the ball rolls over the same ground the grass is placed on
+fn place_ball_on_ground(
+    time: Res<Time>,
+    images: Res<Assets<Image>>,
+    grass: Query<(&GrassSpawner, &Aabb)>,
+    mut balls: Query<&mut Transform, With<Ball>>,
+) {
+    let t = time.elapsed_seconds() / 4.;
+    let xz = Vec2::new(50. + t.cos() * 30., 50. + t.sin() * 30.);
+    for (spawner, aabb) in &grass {
+        let Some(y) = spawner.sample_ground_height(&images, aabb, xz) else {
+            continue;
+        };
+        for mut transform in &mut balls {
+            transform.translation = Vec3::new(xz.x, y + 1., xz.y);
+        }
+    }
 }
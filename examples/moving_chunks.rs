@@ -0,0 +1,98 @@
+use bevy::{prelude::*, render::primitives::Aabb};
+use warbler_grass::{
+    grass_spawner::GrassSpawner,
+    height_map::{HeightMap, HeightMapFilter},
+    warblers_plugin::WarblersPlugin,
+    WarblersBundle,
+};
+mod helper;
+
+/// A grass chunk which tilts and turns like the deck of a ship
+#[derive(Component)]
+struct Deck;
+
+/// A ball which stays on the ground of the deck
+#[derive(Component)]
+struct Ball;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .add_system(move_deck)
+        .add_system(place_ball_on_deck.after(move_deck))
+        .run();
+}
+fn setup_grass(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let height_map = asset_server.load("grass_height_map.png");
+    let positions_xz: Vec<Vec2> = (0..10_000)
+        .map(|i| Vec2::new((i / 100) as f32, (i % 100) as f32) / 2. - 25.)
+        .collect();
+    let height_map = HeightMap::new(height_map, 4.).with_filter(HeightMapFilter::Bilinear);
+    let grass_spawner = GrassSpawner::new()
+        .with_positions_xz(positions_xz)
+        .with_height_map(height_map);
+    commands.spawn((
+        WarblersBundle {
+            grass_spawner,
+            ..default()
+        },
+        Deck,
+    ));
+    // a stretched chunk standing still next to the deck
+    commands.spawn(WarblersBundle {
+        grass_spawner: helper::get_grass_grid(),
+        spatial: SpatialBundle::from_transform(
+            Transform::from_xyz(40., 0., -25.).with_scale(Vec3::new(0.5, 2., 1.)),
+        ),
+        ..default()
+    });
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(shape::UVSphere::default().into()),
+            material: materials.add(Color::WHITE.into()),
+            ..default()
+        },
+        Ball,
+    ));
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(0., 30., 0.),
+        ..default()
+    });
+}
+fn move_deck(time: Res<Time>, mut decks: Query<&mut Transform, With<Deck>>) {
+    let t = time.elapsed_seconds();
+    for mut transform in &mut decks {
+        transform.translation = Vec3::new(0., (t * 0.8).sin() * 2., 0.);
+        transform.rotation = Quat::from_rotation_y(t * 0.1)
+            * Quat::from_rotation_x((t * 0.5).sin() * 0.1)
+            * Quat::from_rotation_z((t * 0.7).cos() * 0.1);
+    }
+}
+// the ball rolls in a circle, staying on the ground of the moving deck
+fn place_ball_on_deck(
+    time: Res<Time>,
+    images: Res<Assets<Image>>,
+    decks: Query<(&GrassSpawner, &Aabb, &Transform), With<Deck>>,
+    mut balls: Query<&mut Transform, (With<Ball>, Without<Deck>)>,
+) {
+    let t = time.elapsed_seconds() / 4.;
+    for (spawner, aabb, transform) in &decks {
+        // the global transform of the deck isn't updated yet, so it's calculated from the transform
+        let deck = GlobalTransform::from(*transform);
+        let above = deck.transform_point(Vec3::new(t.cos() * 15., 10., t.sin() * 15.));
+        let Some(ground) = spawner.sample_ground_position(&images, aabb, &deck, above) else {
+            continue;
+        };
+        for mut ball_transform in &mut balls {
+            ball_transform.translation = ground + deck.up();
+        }
+    }
+}
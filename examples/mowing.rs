@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+use warbler_grass::{
+    grass_spawner::{GrassRegrowth, GrassSpawner},
+    prelude::*,
+    region::GrassRegion,
+};
+mod helper;
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .add_system(mow_grass)
+        .run();
+}
+fn setup_grass(mut commands: Commands) {
+    commands.spawn((
+        WarblersBundle {
+            grass_spawner: helper::get_grass_grid().with_height_uniform(1.5),
+            ..default()
+        },
+        // the mowed grass grows back slowly
+        GrassRegrowth { speed: 0.2 },
+    ));
+}
+// mows a circle which moves over the field
+fn mow_grass(time: Res<Time>, mut grass: Query<&mut GrassSpawner>) {
+    let t = time.elapsed_seconds() / 2.;
+    let region = GrassRegion::Circle {
+        center: Vec2::new(25. + t.cos() * 15., 25. + t.sin() * 15.),
+        radius: 3.,
+    };
+    for mut spawner in &mut grass {
+        spawner.mow(&region, 0.2);
+    }
+}
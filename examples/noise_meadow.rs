@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use warbler_grass::{
+    grass_spawner::GrassSpawner,
+    noise::{FractalNoise, NoiseKind},
+    prelude::*,
+    region::GrassRegion,
+    scatter::ScatterPattern,
+};
+mod helper;
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .run();
+}
+fn setup_grass(mut commands: Commands) {
+    let heights = FractalNoise::new(1).with_frequency(0.08);
+    let density = FractalNoise::new(2).with_frequency(0.05).with_octaves(2);
+    let colors = FractalNoise::new(3)
+        .with_kind(NoiseKind::Value)
+        .with_frequency(0.1);
+    let region = GrassRegion::Rect {
+        min: Vec2::ZERO,
+        max: Vec2::splat(25.),
+    };
+    // the chunks sample the noise at their world position, so they fit together without seams
+    for x in 0..2 {
+        for z in 0..2 {
+            let offset = Vec2::new(x as f32, z as f32) * 25.;
+            let seed = (x * 2 + z) as u64;
+            let grass_spawner = GrassSpawner::scatter_in_region(
+                &region,
+                ScatterPattern::PoissonDisk { min_distance: 0.2 },
+                seed,
+            )
+            .with_noise_density(&density, offset, seed)
+            .with_noise_heights(&heights, 0.5, 2., offset)
+            .with_noise_colors(
+                &colors,
+                Color::WHITE,
+                Color::rgb(1.4, 1.3, 0.4),
+                offset,
+            );
+            commands.spawn(WarblersBundle {
+                grass_spawner,
+                spatial: SpatialBundle {
+                    transform: Transform::from_xyz(offset.x, 0., offset.y),
+                    ..default()
+                },
+                ..default()
+            });
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use bevy::{prelude::*, render::mesh::Indices, render::render_resource::PrimitiveTopology};
+use warbler_grass::{grass_spawner::GrassSpawner, prelude::*};
+mod helper;
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .run();
+}
+fn setup_grass(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let terrain = hilly_terrain();
+    // the grass is placed exactly on the surface of the terrain
+    let grass_spawner = GrassSpawner::scatter_on_mesh(&terrain, 10., 42);
+    commands.spawn(WarblersBundle {
+        grass_spawner,
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(terrain),
+        material: materials.add(Color::rgb(0.1, 0.1, 0.0).into()),
+        ..default()
+    });
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_xyz(1., 2., 1.).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
+// a simple grid of quads with some hills
+fn hilly_terrain() -> Mesh {
+    let size = 50;
+    let positions: Vec<[f32; 3]> = (0..=size)
+        .flat_map(|x| (0..=size).map(move |z| (x as f32, z as f32)))
+        .map(|(x, z)| [x, (x / 5.).sin() * (z / 7.).cos() * 2., z])
+        .collect();
+    let indices = (0..size)
+        .flat_map(|x| (0..size).map(move |z| x * (size + 1) + z))
+        .flat_map(|i| [i, i + 1, i + size + 1, i + 1, i + size + 2, i + size + 1])
+        .collect();
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh.duplicate_vertices();
+    mesh.compute_flat_normals();
+    mesh
+}
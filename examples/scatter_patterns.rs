@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use warbler_grass::{
+    exclusion::GrassExclusion, grass_spawner::GrassSpawner, prelude::*, region::GrassRegion,
+    scatter::ScatterPattern,
+};
+mod helper;
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .run();
+}
+fn setup_grass(mut commands: Commands) {
+    let patterns = [
+        ScatterPattern::JitteredGrid {
+            spacing: 0.3,
+            jitter: 1.,
+        },
+        ScatterPattern::PoissonDisk { min_distance: 0.3 },
+        ScatterPattern::BlueNoise { count: 5000 },
+    ];
+    // the patterns are placed in triangles next to each other
+    let region = GrassRegion::Polygon(vec![Vec2::ZERO, Vec2::new(20., 0.), Vec2::new(10., 20.)]);
+    // a path with soft edges leads through the grass
+    let path = GrassExclusion::new()
+        .with_region(GrassRegion::Rect {
+            min: Vec2::new(9., 0.),
+            max: Vec2::new(11., 20.),
+        })
+        .with_soft_edge(1.);
+    for (i, pattern) in patterns.into_iter().enumerate() {
+        let seed = i as u64;
+        commands.spawn(WarblersBundle {
+            grass_spawner: GrassSpawner::scatter_in_region(&region, pattern, seed)
+                .with_exclusion(&path, seed),
+            spatial: SpatialBundle {
+                transform: Transform::from_xyz(i as f32 * 25., 0., 0.),
+                ..default()
+            },
+            ..default()
+        });
+    }
+}
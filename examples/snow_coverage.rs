@@ -0,0 +1,72 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use warbler_grass::prelude::*;
+mod helper;
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .add_system(change_season)
+        .run();
+}
+fn setup_grass(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut config: ResMut<GrassConfiguration>,
+) {
+    commands.spawn((WarblersBundle {
+        grass_spawner: helper::get_grass_grid(),
+        ..default()
+    },));
+    // the second chunk is always dusty, regardless of the global coverage.
+    // Its own mask lets the dust only lie on the front half of the chunk
+    commands.spawn((
+        WarblersBundle {
+            grass_spawner: helper::get_grass_grid(),
+            spatial: SpatialBundle::from_transform(Transform::from_xyz(55., 0., 0.)),
+            ..default()
+        },
+        GrassCoverage::dust(0.8),
+        GrassCoverageMask::new(
+            images.add(gradient_mask(|v| if v < 0.5 { 1. } else { 0. })),
+            Vec2::new(55., 0.),
+            Vec2::splat(50.),
+        ),
+    ));
+    config.coverage = GrassCoverage::snow(0.);
+    // the global mask lets less snow lie towards the far end of the first chunk
+    config.coverage_mask = Some(GrassCoverageMask::new(
+        images.add(gradient_mask(|v| 1. - v)),
+        Vec2::ZERO,
+        Vec2::splat(50.),
+    ));
+}
+// creates a mask whose value only depends on the z position, given in the range [0, 1]
+fn gradient_mask(value: impl Fn(f32) -> f32) -> Image {
+    let resolution = 64;
+    let data = (0..resolution * resolution)
+        .flat_map(|i| {
+            let v = (i / resolution) as f32 / resolution as f32;
+            [(value(v) * 255.) as u8, 0, 0, 255]
+        })
+        .collect();
+    Image::new(
+        Extent3d {
+            width: resolution,
+            height: resolution,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+    )
+}
+// the snow comes and goes with the seasons
+fn change_season(time: Res<Time>, mut config: ResMut<GrassConfiguration>) {
+    let season = (time.elapsed_seconds() / 3.).sin();
+    config.coverage.amount = season.max(0.);
+}
@@ -3,7 +3,7 @@ use bevy::{
     prelude::*,
     window::PresentMode,
 };
-use warbler_grass::{grass_spawner::GrassSpawner, prelude::*};
+use warbler_grass::{diagnostic::GrassDiagnosticsPlugin, grass_spawner::GrassSpawner, prelude::*};
 mod helper;
 fn main() {
     App::new()
@@ -15,6 +15,7 @@ fn main() {
             ..default()
         }))
         .add_plugin(WarblersPlugin)
+        .add_plugin(GrassDiagnosticsPlugin)
         .add_plugin(LogDiagnosticsPlugin::default())
         .add_plugin(helper::SimpleCamera)
         .add_startup_system(setup_grass)
@@ -34,7 +35,6 @@ pub fn diagnostic_system(mut diagnostics: ResMut<Diagnostics>, time: Res<Time>)
 }
 fn setup_grass(mut commands: Commands) {
     let positions = (0..10_000_000)
-        .into_iter()
         .map(|i| {
             let i = i as f32;
             (i % 2000., i / 1000.)
@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use warbler_grass::prelude::*;
+mod helper;
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup_grass)
+        .add_system(move_displacer)
+        .run();
+}
+fn setup_grass(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((WarblersBundle {
+        grass_spawner: helper::get_grass_grid(),
+        ..default()
+    },));
+    // the trail map covers the whole grass grid
+    let trail_map =
+        GrassTrailMap::new(&mut images, Vec2::ZERO, Vec2::splat(50.), UVec2::splat(256));
+    commands.insert_resource(trail_map);
+
+    // the ball tramples the grass it rolls over
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(shape::UVSphere::default().into()),
+            material: materials.add(Color::WHITE.into()),
+            ..default()
+        },
+        GrassDisplacer {
+            radius: 1.5,
+            strength: 1.,
+        },
+    ));
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(25., 20., 25.),
+        ..default()
+    });
+}
+fn move_displacer(time: Res<Time>, mut query: Query<&mut Transform, With<GrassDisplacer>>) {
+    let t = time.elapsed_seconds() / 4.;
+    for mut transform in &mut query {
+        transform.translation = Vec3::new(25. + t.cos() * 15., 1., 25. + (2. * t).sin() * 10.);
+    }
+}
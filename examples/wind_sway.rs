@@ -0,0 +1,169 @@
+use bevy::{
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+use warbler_grass::{
+    clock::GrassClock,
+    warblers_plugin::WarblersPlugin,
+    wind::{wind_displacement, GrassWindMaterial, GrassWindPlugin},
+    GrassConfiguration, WarblersBundle,
+};
+mod helper;
+
+/// A leaf floating above the grass, which is moved by the wind on the CPU
+#[derive(Component)]
+struct Leaf {
+    position: Vec3,
+}
+
+/// A material moving its vertices in the same wind as the grass
+#[derive(AsBindGroup, TypeUuid, Clone)]
+#[uuid = "4a3d7e2c-91b5-4c0f-8e6a-7d2b9f1c5e38"]
+struct SwayingMaterial {
+    #[uniform(0)]
+    color: Color,
+    #[uniform(0)]
+    sway_height: f32,
+    #[texture(1, sample_type = "float", filterable = false)]
+    wind: Handle<Image>,
+    #[texture(2)]
+    wind_noise: Handle<Image>,
+}
+
+impl Material for SwayingMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/swaying.wgsl".into()
+    }
+    fn fragment_shader() -> ShaderRef {
+        "shaders/swaying.wgsl".into()
+    }
+}
+
+impl GrassWindMaterial for SwayingMaterial {
+    fn set_grass_wind(&mut self, wind_texture: &Handle<Image>, noise_texture: &Handle<Image>) {
+        self.wind = wind_texture.clone();
+        self.wind_noise = noise_texture.clone();
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(WarblersPlugin)
+        .add_plugin(MaterialPlugin::<SwayingMaterial>::default())
+        .add_plugin(GrassWindPlugin::<SwayingMaterial>::default())
+        .add_plugin(helper::SimpleCamera)
+        .add_startup_system(setup)
+        .add_system(change_wind)
+        .add_system(move_leaves)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<SwayingMaterial>>,
+    mut standard_materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(WarblersBundle {
+        grass_spawner: helper::get_grass_grid(),
+        ..default()
+    });
+    // the wind textures are set by the GrassWindPlugin once the material is created
+    let material = materials.add(SwayingMaterial {
+        color: Color::rgb(0.4, 0.25, 0.1),
+        sway_height: 4.,
+        wind: Handle::default(),
+        wind_noise: Handle::default(),
+    });
+    let mesh = meshes.add(shape::Box::new(0.2, 4., 0.2).into());
+    for x in 0..5 {
+        for z in 0..5 {
+            commands.spawn(MaterialMeshBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                // the box is moved up, so its origin is at the bottom
+                transform: Transform::from_xyz(x as f32 * 4. + 2., 2., z as f32 * 4. + 2.),
+                ..default()
+            });
+        }
+    }
+    let leaf_mesh = meshes.add(
+        shape::UVSphere {
+            radius: 0.15,
+            ..default()
+        }
+        .into(),
+    );
+    let leaf_material = standard_materials.add(StandardMaterial {
+        base_color: Color::ORANGE,
+        unlit: true,
+        ..default()
+    });
+    for x in 0..4 {
+        for z in 0..4 {
+            let position = Vec3::new(x as f32 * 4. + 4., 1.2, z as f32 * 4. + 4.);
+            commands.spawn((
+                PbrBundle {
+                    mesh: leaf_mesh.clone(),
+                    material: leaf_material.clone(),
+                    transform: Transform::from_translation(position),
+                    ..default()
+                },
+                Leaf { position },
+            ));
+        }
+    }
+}
+
+// the leaves are moved as much as the tips of the grass blades below them
+fn move_leaves(
+    config: Res<GrassConfiguration>,
+    clock: Res<GrassClock>,
+    images: Res<Assets<Image>>,
+    mut leaves: Query<(&Leaf, &mut Transform)>,
+) {
+    let Some(noise) = images.get(&config.wind_noise_texture) else {
+        return;
+    };
+    for (leaf, mut transform) in &mut leaves {
+        let displacement = wind_displacement(
+            &config,
+            noise,
+            &clock,
+            Vec2::new(leaf.position.x, leaf.position.z),
+            1.,
+        )
+        .unwrap_or_default();
+        transform.translation = leaf.position + Vec3::new(displacement.x, 0., displacement.y);
+    }
+}
+
+// the up and down arrow keys change the strength of the wind,
+// the left and right arrow keys change the speed of the wind and space pauses the wind
+fn change_wind(
+    input: Res<Input<KeyCode>>,
+    mut config: ResMut<GrassConfiguration>,
+    mut clock: ResMut<GrassClock>,
+) {
+    if input.just_pressed(KeyCode::Up) {
+        config.wind *= 1.5;
+    }
+    if input.just_pressed(KeyCode::Down) {
+        config.wind /= 1.5;
+    }
+    if input.just_pressed(KeyCode::Right) {
+        clock.speed *= 2.;
+    }
+    if input.just_pressed(KeyCode::Left) {
+        clock.speed /= 2.;
+    }
+    if input.just_pressed(KeyCode::Space) {
+        if clock.is_paused() {
+            clock.unpause();
+        } else {
+            clock.pause();
+        }
+    }
+}
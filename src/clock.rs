@@ -0,0 +1,106 @@
+use bevy::{prelude::*, render::extract_resource::ExtractResource};
+
+/// The time passed to shaders wraps around after this many seconds, to keep its precision as a 32-bit float.
+///
+/// The wind doesn't use the wrapped time, see [`wind_noise_offset`](crate::wind::wind_noise_offset)
+const WRAP_PERIOD: f64 = 3600.;
+
+/// The source of time the [`GrassClock`] follows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, FromReflect)]
+pub enum GrassClockSource {
+    /// Follows the virtual [`Time`], so the clock stops if the time is paused
+    /// and respects the relative speed of the time
+    #[default]
+    Virtual,
+    /// Follows the real time, ignoring pauses and the relative speed of [`Time`]
+    Real,
+    /// Only advances if the clock is set or advanced explicitly,
+    /// for example to replay a recorded game or to take deterministic screenshots
+    Manual,
+}
+
+/// A [resource](bevy::prelude::Resource) defining the time used to animate the wind of the grass.
+///
+/// By default the clock follows the virtual [`Time`].
+/// It can be paused, scaled or set explicitly, independently of the time of the game.
+/// A default [`GrassClock`] is inserted by the [`WarblersPlugin`](crate::warblers_plugin::WarblersPlugin).
+#[derive(Resource, Clone, Debug, Reflect, ExtractResource)]
+#[reflect(Resource)]
+pub struct GrassClock {
+    pub source: GrassClockSource,
+    /// The factor by which the clock runs faster than its source
+    pub speed: f32,
+    paused: bool,
+    elapsed: f64,
+}
+
+impl Default for GrassClock {
+    fn default() -> Self {
+        GrassClock {
+            source: GrassClockSource::default(),
+            speed: 1.,
+            paused: false,
+            elapsed: 0.,
+        }
+    }
+}
+
+impl GrassClock {
+    pub fn new(source: GrassClockSource) -> Self {
+        GrassClock {
+            source,
+            ..default()
+        }
+    }
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+    /// The seconds elapsed on the clock, as seen by custom shaders reading the `time` of the grass wind.
+    ///
+    /// The time wraps around to zero after an hour.
+    /// The wind itself is calculated from the [`Self::elapsed_seconds`], so it doesn't jump when the time wraps
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed.rem_euclid(WRAP_PERIOD) as f32
+    }
+    /// The seconds elapsed on the clock with full precision
+    pub fn elapsed_seconds(&self) -> f64 {
+        self.elapsed
+    }
+    /// Sets the elapsed seconds of the clock, independent of its source
+    pub fn set_elapsed(&mut self, seconds: f64) {
+        self.elapsed = seconds;
+    }
+    /// Advances the clock by the seconds, scaled by its speed.
+    ///
+    /// The clock is advanced even if it's paused
+    pub fn advance(&mut self, seconds: f64) {
+        self.set_elapsed(self.elapsed + seconds * self.speed as f64);
+    }
+    /// Stops the clock from following its source
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+    /// Lets the clock follow its source again
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+/// Advances the [`GrassClock`] by the time passed since the last frame
+pub(crate) fn update_grass_clock(time: Res<Time>, mut clock: ResMut<GrassClock>) {
+    // the clock only triggers change detection if it changes, so it isn't extracted needlessly
+    let delta = match clock.source {
+        _ if clock.paused => return,
+        GrassClockSource::Virtual => time.delta_seconds_f64(),
+        GrassClockSource::Real => time.raw_delta_seconds_f64(),
+        GrassClockSource::Manual => return,
+    };
+    if delta == 0. || clock.speed == 0. {
+        return;
+    }
+    clock.advance(delta);
+}
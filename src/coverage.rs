@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+/// Covers the upper part of the blades with a color, for example snow or dust.
+///
+/// The global coverage is set by [`GrassConfiguration::coverage`](crate::GrassConfiguration::coverage).
+/// Adding a [`GrassCoverage`] component to a grass chunk overrides the global coverage for that chunk.
+/// Seasonal transitions can be made by animating the `amount`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect, FromReflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct GrassCoverage {
+    /// How much the blades are covered. Should be in the range `[0, 1]`.
+    ///
+    /// An amount of zero disables the coverage
+    pub amount: f32,
+    /// The [Color] of the coverage
+    pub color: Color,
+    /// The height above which the blades are covered,
+    /// relative to the height of the blade, so `0` is the base and `1` is the tip
+    pub height_threshold: f32,
+}
+impl Default for GrassCoverage {
+    fn default() -> Self {
+        GrassCoverage {
+            amount: 0.,
+            color: Color::WHITE,
+            height_threshold: 0.5,
+        }
+    }
+}
+impl GrassCoverage {
+    /// A coverage looking like snow
+    pub fn snow(amount: f32) -> Self {
+        GrassCoverage {
+            amount,
+            color: Color::rgb(0.95, 0.97, 1.),
+            height_threshold: 0.4,
+        }
+    }
+    /// A coverage looking like dust
+    pub fn dust(amount: f32) -> Self {
+        GrassCoverage {
+            amount,
+            color: Color::rgb(0.6, 0.52, 0.4),
+            height_threshold: 0.2,
+        }
+    }
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+    pub fn with_height_threshold(mut self, height_threshold: f32) -> Self {
+        self.height_threshold = height_threshold;
+        self
+    }
+}
+
+/// A world space texture limiting where the grass is covered by the [`GrassCoverage`].
+///
+/// The red channel of the texture scales the amount of the coverage,
+/// the coverage is not limited outside of the area of the mask.
+///
+/// The global mask is set by [`GrassConfiguration::coverage_mask`](crate::GrassConfiguration::coverage_mask).
+/// Adding a [`GrassCoverageMask`] component to a grass chunk overrides the global mask for that chunk.
+#[derive(Component, Clone, Debug, Default, Reflect, FromReflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[reflect(Component)]
+pub struct GrassCoverageMask {
+    /// The texture of the mask.
+    ///
+    /// The texture is not serialized, so it needs to be set again after deserializing the mask.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub texture: Handle<Image>,
+    /// The world position of the corner of the mask with the lowest x and z coordinates.
+    pub origin: Vec2,
+    /// The size of the area covered by the mask on the x,z plane.
+    pub size: Vec2,
+}
+impl GrassCoverageMask {
+    pub fn new(texture: Handle<Image>, origin: Vec2, size: Vec2) -> Self {
+        GrassCoverageMask {
+            texture,
+            origin,
+            size,
+        }
+    }
+}
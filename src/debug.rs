@@ -0,0 +1,406 @@
+use bevy::{
+    pbr::NotShadowCaster,
+    prelude::*,
+    render::{primitives::Aabb, render_resource::PrimitiveTopology, view::NoFrustumCulling},
+    utils::HashMap,
+};
+
+use crate::grass_spawner::{GrassSpawner, GrassSpawnerFlags, HeightRepresentation};
+
+/// Draws debug information of all grass chunks.
+///
+/// Each chunk shows its [`Aabb`], colored by its [`GrassChunkState`], and the extent of its height map.
+/// The drawn information can be configured using the [`GrassDebugSettings`] resource.
+///
+/// Since the [`WarblersPlugin`](crate::warblers_plugin::WarblersPlugin) is needed to draw grass at all,
+/// this plugin should be added after it.
+pub struct WarblersDebugPlugin;
+impl Plugin for WarblersDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GrassDebugSettings>()
+            .init_resource::<GrassDebugMaterial>()
+            .register_type::<GrassDebugSettings>()
+            .register_type::<GrassChunkDebugInfo>()
+            .add_system(update_debug_info.in_base_set(CoreSet::PostUpdate))
+            .add_system(
+                update_debug_lines
+                    .in_base_set(CoreSet::PostUpdate)
+                    .after(update_debug_info),
+            )
+            .add_system(
+                update_debug_labels
+                    .in_base_set(CoreSet::PostUpdate)
+                    .after(update_debug_info),
+            );
+    }
+}
+
+/// A [resource](bevy::prelude::Resource) to configure the [`WarblersDebugPlugin`].
+#[derive(Resource, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct GrassDebugSettings {
+    /// Draws the [`Aabb`] of each chunk, colored by its [`GrassChunkState`].
+    pub show_aabb: bool,
+    /// Draws the area covered by the height map of each chunk, from the lowest to the highest possible y position.
+    pub show_height_map_extent: bool,
+    /// Draws a line for each blade, from its position to its tip.
+    ///
+    /// Useful to inspect the positions of blades, but slow for large chunks.
+    pub show_blades: bool,
+    /// The font of the labels showing the blade count and state of each chunk.
+    ///
+    /// The labels are only shown if a font is set.
+    pub label_font: Option<Handle<Font>>,
+}
+impl Default for GrassDebugSettings {
+    fn default() -> Self {
+        GrassDebugSettings {
+            show_aabb: true,
+            show_height_map_extent: true,
+            show_blades: false,
+            label_font: None,
+        }
+    }
+}
+
+/// The state of a grass chunk, as shown by the [`WarblersDebugPlugin`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, FromReflect)]
+pub enum GrassChunkState {
+    /// The height map of the chunk is still loading
+    #[default]
+    Pending,
+    /// The chunk is drawn
+    Ready,
+    /// The chunk is outside of the view of all cameras or hidden
+    Culled,
+    /// The chunk has no blades yet, so nothing is drawn
+    Empty,
+    /// The data of the chunk can't be drawn, for example because the y positions of the blades are missing
+    Invalid,
+}
+impl GrassChunkState {
+    /// The color used to draw chunks in this state
+    pub fn color(self) -> Color {
+        match self {
+            GrassChunkState::Pending => Color::YELLOW,
+            GrassChunkState::Ready => Color::GREEN,
+            GrassChunkState::Culled => Color::GRAY,
+            GrassChunkState::Empty => Color::BLUE,
+            GrassChunkState::Invalid => Color::RED,
+        }
+    }
+}
+
+/// Debug information about a grass chunk.
+///
+/// The component is added to all grass chunks by the [`WarblersDebugPlugin`].
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct GrassChunkDebugInfo {
+    pub state: GrassChunkState,
+    pub blade_count: usize,
+}
+
+/// The material used for all debug lines. The lines are colored using vertex colors
+#[derive(Resource)]
+struct GrassDebugMaterial(Handle<StandardMaterial>);
+impl FromWorld for GrassDebugMaterial {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        GrassDebugMaterial(materials.add(StandardMaterial {
+            base_color: Color::WHITE,
+            unlit: true,
+            ..default()
+        }))
+    }
+}
+
+/// The mesh drawing the debug lines of a grass chunk
+#[derive(Component)]
+struct GrassDebugVisuals {
+    lines: Handle<Mesh>,
+}
+
+const HEIGHT_MAP_EXTENT_COLOR: Color = Color::CYAN;
+const BLADE_COLOR: Color = Color::WHITE;
+
+fn chunk_state(
+    spawner: &GrassSpawner,
+    visibility: &ComputedVisibility,
+    images: &Assets<Image>,
+) -> GrassChunkState {
+    let blade_count = spawner.positions_xz.len();
+    let y_defined = spawner.flags.contains(GrassSpawnerFlags::HEIGHT_MAP)
+        || spawner.positions_y.len() == blade_count;
+    if blade_count == 0 {
+        return GrassChunkState::Empty;
+    }
+    if !y_defined {
+        return GrassChunkState::Invalid;
+    }
+    if let Some(height_map) = &spawner.height_map {
+        if images.get(&height_map.height_map).is_none() {
+            return GrassChunkState::Pending;
+        }
+    }
+    if !visibility.is_visible() {
+        return GrassChunkState::Culled;
+    }
+    GrassChunkState::Ready
+}
+
+fn update_debug_info(
+    mut commands: Commands,
+    images: Res<Assets<Image>>,
+    mut chunks: Query<(
+        Entity,
+        &GrassSpawner,
+        &ComputedVisibility,
+        Option<&mut GrassChunkDebugInfo>,
+    )>,
+) {
+    for (entity, spawner, visibility, info) in chunks.iter_mut() {
+        let new_info = GrassChunkDebugInfo {
+            state: chunk_state(spawner, visibility, &images),
+            blade_count: spawner.positions_xz.len(),
+        };
+        match info {
+            // avoids triggering change detection, since the lines are rebuilt on change
+            Some(mut info) => {
+                if info.state != new_info.state || info.blade_count != new_info.blade_count {
+                    *info = new_info;
+                }
+            }
+            None => {
+                commands.entity(entity).insert(new_info);
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_debug_lines(
+    mut commands: Commands,
+    settings: Res<GrassDebugSettings>,
+    material: Res<GrassDebugMaterial>,
+    images: Res<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    chunks: Query<(
+        Entity,
+        Ref<GrassSpawner>,
+        Ref<Aabb>,
+        Ref<GrassChunkDebugInfo>,
+        Option<&GrassDebugVisuals>,
+    )>,
+) {
+    for (entity, spawner, aabb, info, visuals) in chunks.iter() {
+        let changed =
+            settings.is_changed() || spawner.is_changed() || aabb.is_changed() || info.is_changed();
+        if !changed {
+            continue;
+        }
+        let mut lines = DebugLines::default();
+        if settings.show_aabb {
+            lines.add_box(aabb.min().into(), aabb.max().into(), info.state.color());
+        }
+        if let (true, Some(height_map)) = (settings.show_height_map_extent, &spawner.height_map) {
+            let extent = height_map.extent_in(&aabb);
+            let min = Vec3::new(extent.origin.x, height_map.base_offset, extent.origin.y);
+            let max = min + Vec3::new(extent.size.x, height_map.height, extent.size.y);
+            lines.add_box(min, max, HEIGHT_MAP_EXTENT_COLOR);
+        }
+        if settings.show_blades {
+            add_blade_lines(&mut lines, &spawner, &aabb, &images);
+        }
+        let mesh = lines.into_mesh();
+        match visuals {
+            Some(visuals) => {
+                let _ = meshes.set(&visuals.lines, mesh);
+            }
+            None => {
+                let handle = meshes.add(mesh);
+                let child = commands
+                    .spawn((
+                        PbrBundle {
+                            mesh: handle.clone(),
+                            material: material.0.clone(),
+                            ..default()
+                        },
+                        NotShadowCaster,
+                        // the bounds of the lines change whenever the mesh is rebuilt
+                        NoFrustumCulling,
+                    ))
+                    .id();
+                commands
+                    .entity(entity)
+                    .add_child(child)
+                    .insert(GrassDebugVisuals { lines: handle });
+            }
+        }
+    }
+}
+
+/// Adds a line from the position to the tip of each blade
+fn add_blade_lines(
+    lines: &mut DebugLines,
+    spawner: &GrassSpawner,
+    aabb: &Aabb,
+    images: &Assets<Image>,
+) {
+    for (i, xz) in spawner.positions_xz.iter().enumerate() {
+        let y = match spawner.positions_y.get(i) {
+            Some(y) => *y,
+            None => spawner
+                .sample_ground_height(images, aabb, *xz)
+                .unwrap_or(0.),
+        };
+        let height = match &spawner.heights {
+            HeightRepresentation::Uniform(height) => *height,
+            HeightRepresentation::PerBlade(heights) => heights.get(i).copied().unwrap_or(0.),
+        };
+        let base = Vec3::new(xz.x, y, xz.y);
+        lines.add_line(base, base + Vec3::Y * height, BLADE_COLOR);
+    }
+}
+
+/// Shows the blade count and state of each chunk at the center of its [`Aabb`]
+#[allow(clippy::type_complexity)]
+fn update_debug_labels(
+    mut commands: Commands,
+    settings: Res<GrassDebugSettings>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    chunks: Query<(Entity, &GrassChunkDebugInfo, &Aabb, &GlobalTransform), With<GrassSpawner>>,
+    mut labels: Query<(&mut Text, &mut Style)>,
+    // the labels aren't children of the chunks, so they are tracked here to remove them together with the chunk
+    mut chunk_labels: Local<HashMap<Entity, Entity>>,
+) {
+    // removes the labels of chunks which were despawned or are no grass chunks anymore
+    chunk_labels.retain(|chunk, label| {
+        let keep = settings.label_font.is_some() && chunks.contains(*chunk);
+        if !keep {
+            commands.entity(*label).despawn_recursive();
+        }
+        keep
+    });
+    let Some(font) = &settings.label_font else {
+        return;
+    };
+    let camera = cameras.iter().find(|(camera, _)| camera.is_active);
+    for (chunk, info, aabb, transform) in chunks.iter() {
+        let text = format!("{} blades\n{:?}", info.blade_count, info.state);
+        let style = TextStyle {
+            font: font.clone(),
+            font_size: 16.,
+            color: info.state.color(),
+        };
+        let center = transform.transform_point(aabb.center.into());
+        let screen_position = camera.and_then(|(camera, camera_transform)| {
+            camera.world_to_viewport(camera_transform, center)
+        });
+        let Some(label) = chunk_labels.get(&chunk) else {
+            let label = commands
+                .spawn(TextBundle::from_section(text, style).with_style(Style {
+                    position_type: PositionType::Absolute,
+                    display: Display::None,
+                    ..default()
+                }))
+                .id();
+            chunk_labels.insert(chunk, label);
+            continue;
+        };
+        let Ok((mut label_text, mut label_style)) = labels.get_mut(*label) else {
+            continue;
+        };
+        label_text.sections[0].value = text;
+        label_text.sections[0].style = style;
+        match screen_position {
+            Some(position) => {
+                label_style.display = Display::Flex;
+                // the viewport position starts at the bottom of the window
+                label_style.position = UiRect {
+                    left: Val::Px(position.x),
+                    bottom: Val::Px(position.y),
+                    ..default()
+                };
+            }
+            None => label_style.display = Display::None,
+        }
+    }
+}
+
+/// Collects colored lines which are drawn as a single [`Mesh`]
+#[derive(Default)]
+struct DebugLines {
+    positions: Vec<[f32; 3]>,
+    colors: Vec<[f32; 4]>,
+}
+impl DebugLines {
+    fn add_line(&mut self, start: Vec3, end: Vec3, color: Color) {
+        self.positions.extend([start.to_array(), end.to_array()]);
+        self.colors.extend([color.as_linear_rgba_f32(); 2]);
+    }
+    fn add_box(&mut self, min: Vec3, max: Vec3, color: Color) {
+        let corner = |x: bool, y: bool, z: bool| {
+            Vec3::new(
+                if x { max.x } else { min.x },
+                if y { max.y } else { min.y },
+                if z { max.z } else { min.z },
+            )
+        };
+        for a in [false, true] {
+            for b in [false, true] {
+                self.add_line(corner(false, a, b), corner(true, a, b), color);
+                self.add_line(corner(a, false, b), corner(a, true, b), color);
+                self.add_line(corner(a, b, false), corner(a, b, true), color);
+            }
+        }
+    }
+    fn into_mesh(self) -> Mesh {
+        let mut mesh = Mesh::new(PrimitiveTopology::LineList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors);
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label_count(world: &mut World) -> usize {
+        world.query::<&Text>().iter(world).count()
+    }
+
+    #[test]
+    fn labels_are_removed_with_their_chunk() {
+        let mut world = World::new();
+        world.insert_resource(GrassDebugSettings {
+            label_font: Some(Handle::default()),
+            ..default()
+        });
+        let mut schedule = Schedule::new();
+        schedule.add_system(update_debug_labels);
+        let chunk = || {
+            (
+                GrassSpawner::new(),
+                GrassChunkDebugInfo::default(),
+                Aabb::default(),
+                GlobalTransform::default(),
+            )
+        };
+        let despawned = world.spawn(chunk()).id();
+        let no_grass = world.spawn(chunk()).id();
+        world.spawn(chunk());
+        schedule.run(&mut world);
+        assert_eq!(label_count(&mut world), 3);
+
+        world.despawn(despawned);
+        world.entity_mut(no_grass).remove::<GrassSpawner>();
+        schedule.run(&mut world);
+        assert_eq!(label_count(&mut world), 1);
+
+        world.resource_mut::<GrassDebugSettings>().label_font = None;
+        schedule.run(&mut world);
+        assert_eq!(label_count(&mut world), 0);
+    }
+}
@@ -0,0 +1,143 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticId, Diagnostics},
+    prelude::*,
+};
+
+use crate::render::cache::{EntityCache, GrassCache};
+
+/// Adds diagnostics about the costs of rendering grass to [`Diagnostics`].
+///
+/// The values are measured in the render world,
+/// so they lag behind the main world by a frame if pipelined rendering is used.
+///
+/// The [`WarblersPlugin`](crate::warblers_plugin::WarblersPlugin) needs to be added as well.
+#[derive(Default)]
+pub struct GrassDiagnosticsPlugin;
+
+impl Plugin for GrassDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(Self::setup_system)
+            .add_system(Self::diagnostic_system);
+    }
+}
+
+impl GrassDiagnosticsPlugin {
+    /// The number of grass chunks in the render world
+    pub const CHUNK_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(241318939871384790175638410245372036608);
+    /// The number of grass chunks which are visible
+    pub const VISIBLE_CHUNK_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(241318939871384790175638410245372036609);
+    /// The number of blades of all grass chunks
+    pub const BLADE_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(241318939871384790175638410245372036610);
+    /// The number of blades of all visible grass chunks
+    pub const DRAWN_BLADE_COUNT: DiagnosticId =
+        DiagnosticId::from_u128(241318939871384790175638410245372036611);
+    /// The bytes of grass data uploaded to the gpu since the last measurement
+    pub const BYTES_UPLOADED: DiagnosticId =
+        DiagnosticId::from_u128(241318939871384790175638410245372036612);
+    /// The bytes of all textures and buffers held by the grass chunks
+    pub const GPU_MEMORY: DiagnosticId =
+        DiagnosticId::from_u128(241318939871384790175638410245372036613);
+    /// The number of specialized grass render pipelines
+    pub const PIPELINE_SPECIALIZATIONS: DiagnosticId =
+        DiagnosticId::from_u128(241318939871384790175638410245372036614);
+
+    fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(Self::CHUNK_COUNT, "grass_chunk_count", 20));
+        diagnostics.add(Diagnostic::new(
+            Self::VISIBLE_CHUNK_COUNT,
+            "grass_visible_chunk_count",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(Self::BLADE_COUNT, "grass_blade_count", 20));
+        diagnostics.add(Diagnostic::new(
+            Self::DRAWN_BLADE_COUNT,
+            "grass_drawn_blade_count",
+            20,
+        ));
+        diagnostics.add(
+            Diagnostic::new(Self::BYTES_UPLOADED, "grass_bytes_uploaded", 20).with_suffix("B"),
+        );
+        diagnostics.add(Diagnostic::new(Self::GPU_MEMORY, "grass_gpu_memory", 20).with_suffix("B"));
+        diagnostics.add(Diagnostic::new(
+            Self::PIPELINE_SPECIALIZATIONS,
+            "grass_pipeline_specializations",
+            20,
+        ));
+    }
+
+    fn diagnostic_system(mut diagnostics: ResMut<Diagnostics>, stats: Res<GrassRenderStats>) {
+        let load = |value: &AtomicU64| value.load(Ordering::Relaxed) as f64;
+        diagnostics.add_measurement(Self::CHUNK_COUNT, || load(&stats.chunks));
+        diagnostics.add_measurement(Self::VISIBLE_CHUNK_COUNT, || load(&stats.visible_chunks));
+        diagnostics.add_measurement(Self::BLADE_COUNT, || load(&stats.blades));
+        diagnostics.add_measurement(Self::DRAWN_BLADE_COUNT, || load(&stats.drawn_blades));
+        diagnostics.add_measurement(Self::BYTES_UPLOADED, || {
+            stats.bytes_uploaded.swap(0, Ordering::Relaxed) as f64
+        });
+        diagnostics.add_measurement(Self::GPU_MEMORY, || load(&stats.gpu_memory));
+        diagnostics.add_measurement(Self::PIPELINE_SPECIALIZATIONS, || {
+            load(&stats.specializations)
+        });
+    }
+}
+
+/// Statistics of the grass in the render world.
+///
+/// The same statistics are inserted in the main and the render world,
+/// so the render world can share them without extraction.
+#[derive(Resource, Clone, Default, Deref)]
+pub(crate) struct GrassRenderStats(Arc<GrassRenderStatsInner>);
+
+#[derive(Default)]
+pub(crate) struct GrassRenderStatsInner {
+    chunks: AtomicU64,
+    visible_chunks: AtomicU64,
+    blades: AtomicU64,
+    drawn_blades: AtomicU64,
+    /// Accumulates until it's read by the main world
+    bytes_uploaded: AtomicU64,
+    gpu_memory: AtomicU64,
+    pub specializations: AtomicU64,
+}
+impl GrassRenderStatsInner {
+    /// Records bytes written to the gpu, see [`GrassUploads`](crate::render::prepare::GrassUploads)
+    pub fn add_upload(&self, bytes: u64) {
+        self.bytes_uploaded.fetch_add(bytes, Ordering::Relaxed);
+    }
+}
+
+/// Collects the statistics of all grass chunks in the render world.
+///
+/// The uploads are counted when they happen, the chunks of despawned grass are already removed
+/// from the [`GrassCache`] by [`extract_removed_grass`](crate::render::extract::extract_removed_grass).
+pub(crate) fn update_grass_stats(
+    stats: Res<GrassRenderStats>,
+    cache: Res<GrassCache>,
+    entity_cache: Res<EntityCache>,
+) {
+    let (mut blades, mut drawn_blades, mut visible_chunks) = (0, 0, 0);
+    let mut gpu_memory = 0;
+    for (entity, chunk) in cache.iter() {
+        blades += chunk.instance_count as u64;
+        if entity_cache.contains(entity) {
+            visible_chunks += 1;
+            drawn_blades += chunk.instance_count as u64;
+        }
+        gpu_memory += chunk.gpu_memory;
+    }
+    stats.chunks.store(cache.len() as u64, Ordering::Relaxed);
+    stats
+        .visible_chunks
+        .store(visible_chunks, Ordering::Relaxed);
+    stats.blades.store(blades, Ordering::Relaxed);
+    stats.drawn_blades.store(drawn_blades, Ordering::Relaxed);
+    stats.gpu_memory.store(gpu_memory, Ordering::Relaxed);
+}
@@ -0,0 +1,166 @@
+use bevy::{math::Vec3Swizzles, prelude::*, render::primitives::Aabb};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{height_map::texel_value, region::GrassRegion};
+
+/// Areas in which no grass should be generated, like roads, houses or ponds.
+///
+/// Can be applied to any [`GrassSpawner`](crate::grass_spawner::GrassSpawner) using
+/// [`GrassSpawner::with_exclusion`](crate::grass_spawner::GrassSpawner::with_exclusion)
+/// or to generated points using [`GrassExclusion::retain`].
+/// All positions are on the x,z plane, in the same space as the grass blades.
+#[derive(Clone, Default)]
+pub struct GrassExclusion {
+    /// Regions in which no grass grows
+    pub regions: Vec<GrassRegion>,
+    /// Boxes in which no grass grows. Only the extent on the x,z plane is used.
+    pub boxes: Vec<Aabb>,
+    /// A texture in which the red channel defines where grass grows
+    pub mask: Option<ExclusionMask>,
+    /// The width of the border around excluded areas in which the grass gets thinner.
+    ///
+    /// A width of zero creates hard edges.
+    pub soft_edge: f32,
+}
+
+/// A texture defining where grass grows.
+///
+/// Texels with a red value of `1` exclude all grass, texels with a value of `0` exclude none.
+/// Values in between thin out the grass.
+#[derive(Clone)]
+pub struct ExclusionMask {
+    /// The texture of the mask, for example taken from the [`Assets<Image>`]
+    pub image: Image,
+    /// The position of the corner of the mask with the lowest x and z coordinates
+    pub origin: Vec2,
+    /// The size of the area covered by the mask
+    pub size: Vec2,
+}
+
+impl GrassExclusion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Excludes all grass in the region
+    pub fn with_region(mut self, region: GrassRegion) -> Self {
+        self.regions.push(region);
+        self
+    }
+    /// Excludes all grass in the polygon defined by its corners
+    pub fn with_polygon(self, corners: Vec<Vec2>) -> Self {
+        self.with_region(GrassRegion::Polygon(corners))
+    }
+    /// Excludes all grass in the box, as seen from above
+    pub fn with_box(mut self, aabb: Aabb) -> Self {
+        self.boxes.push(aabb);
+        self
+    }
+    /// Excludes grass based on the red channel of an image covering the given area
+    pub fn with_mask(mut self, image: Image, origin: Vec2, size: Vec2) -> Self {
+        assert!(size.x > 0. && size.y > 0.);
+        self.mask = Some(ExclusionMask {
+            image,
+            origin,
+            size,
+        });
+        self
+    }
+    /// Thins out the grass in a border of the given width around the excluded areas
+    pub fn with_soft_edge(mut self, width: f32) -> Self {
+        assert!(width >= 0.);
+        self.soft_edge = width;
+        self
+    }
+    /// Returns the probability of a grass blade at the point to be kept
+    pub fn keep_probability(&self, point: Vec2) -> f32 {
+        let distance = self
+            .regions
+            .iter()
+            .map(|region| region.distance(point))
+            .chain(self.boxes.iter().map(|aabb| {
+                GrassRegion::Rect {
+                    min: aabb.min().xz(),
+                    max: aabb.max().xz(),
+                }
+                .distance(point)
+            }))
+            .fold(f32::MAX, f32::min);
+        let mut probability = if distance <= 0. {
+            0.
+        } else if distance < self.soft_edge {
+            distance / self.soft_edge
+        } else {
+            1.
+        };
+        if let Some(mask) = &self.mask {
+            probability *= 1. - mask.value(point);
+        }
+        probability
+    }
+    /// Removes all items which are placed in excluded areas.
+    ///
+    /// `position` returns the position of an item on the x,z plane.
+    /// The `seed` is used to decide which items are removed in soft edges,
+    /// so the same `seed` always removes the same items.
+    pub fn retain<T>(&self, items: &mut Vec<T>, position: impl Fn(&T) -> Vec2, seed: u64) {
+        let keep = self.keep_mask(items.iter().map(position), seed);
+        let mut keep = keep.into_iter();
+        items.retain(|_| keep.next().unwrap());
+    }
+    /// Decides for each position if a blade is kept
+    pub(crate) fn keep_mask(&self, positions: impl Iterator<Item = Vec2>, seed: u64) -> Vec<bool> {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        positions
+            .map(|position| {
+                let probability = self.keep_probability(position);
+                probability >= 1. || rng.gen::<f32>() < probability
+            })
+            .collect()
+    }
+}
+
+impl ExclusionMask {
+    /// Returns the value of the mask at the point. Points outside of the mask have a value of `0`
+    fn value(&self, point: Vec2) -> f32 {
+        let uv = (point - self.origin) / self.size;
+        if uv.cmplt(Vec2::ZERO).any() || uv.cmpge(Vec2::ONE).any() {
+            return 0.;
+        }
+        let texel = (uv * self.image.size()).as_uvec2();
+        texel_value(&self.image, texel, 0).unwrap_or(0.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soft_edges_thin_out_the_grass() {
+        let exclusion = GrassExclusion::new()
+            .with_region(GrassRegion::Circle {
+                center: Vec2::ZERO,
+                radius: 2.,
+            })
+            .with_box(Aabb::from_min_max(
+                Vec3::new(5., -1., 5.),
+                Vec3::new(6., 1., 6.),
+            ))
+            .with_soft_edge(1.);
+        // inside of the excluded areas
+        assert_eq!(exclusion.keep_probability(Vec2::ZERO), 0.);
+        assert_eq!(exclusion.keep_probability(Vec2::new(0., 2.)), 0.);
+        assert_eq!(exclusion.keep_probability(Vec2::new(5.5, 5.5)), 0.);
+        // in the soft edge
+        assert_eq!(exclusion.keep_probability(Vec2::new(2.5, 0.)), 0.5);
+        assert_eq!(exclusion.keep_probability(Vec2::new(5.5, 6.25)), 0.25);
+        // beyond the soft edge
+        assert_eq!(exclusion.keep_probability(Vec2::new(-3., 0.)), 1.);
+        assert_eq!(exclusion.keep_probability(Vec2::new(7.5, 5.5)), 1.);
+
+        let mut points = vec![Vec2::ZERO, Vec2::new(2.5, 0.), Vec2::new(10., 10.)];
+        exclusion.retain(&mut points, |point| *point, 0);
+        assert!(!points.contains(&Vec2::ZERO));
+        assert!(points.contains(&Vec2::new(10., 10.)));
+    }
+}
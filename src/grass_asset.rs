@@ -0,0 +1,639 @@
+use bevy::{
+    asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
+
+use crate::{
+    grass_spawner::{GrassSpawner, GrassSpawnerFlags, HeightRepresentation},
+    height_map::{HeightMap, HeightMapChannel, HeightMapExtent, HeightMapFilter},
+    warblers_plugin::GRASS_MESH_HANDLE,
+};
+
+/// The bytes every `.grass` file starts with
+const MAGIC: &[u8; 4] = b"WGRS";
+/// The version of the `.grass` format written by [`GrassChunkAsset::encode`]
+const VERSION: u8 = 2;
+/// The deflate level used for compressed `.grass` files
+const COMPRESSION_LEVEL: u8 = 6;
+/// The [`GrassSpawnerFlags`] which can be stored in a `.grass` file
+const FILE_FLAGS: GrassSpawnerFlags = GrassSpawnerFlags::Y_DEFINED
+    .union(GrassSpawnerFlags::XZ_DEFINED)
+    .union(GrassSpawnerFlags::HEIGHT_DEFINED)
+    .union(GrassSpawnerFlags::HEIGHT_MAP)
+    .union(GrassSpawnerFlags::COLOR_DEFINED)
+    .union(GrassSpawnerFlags::GROWTH_DELAYS);
+
+/// A chunk of grass which can be stored in and loaded from a `.grass` file.
+///
+/// Add a `Handle<GrassChunkAsset>` to an entity, for example using a [`GrassChunkBundle`],
+/// and the [`GrassSpawner`] of the entity is created once the asset is loaded.
+/// If the file changes on disk, the grass is updated as well.
+#[derive(TypeUuid, Clone)]
+#[uuid = "5c2e0a4f-8f0b-4b8e-9a53-2f4f7d6c1b92"]
+pub struct GrassChunkAsset {
+    pub spawner: GrassSpawner,
+    /// The asset path of the texture of the [`HeightMap`] of the spawner.
+    ///
+    /// The path is relative to the asset folder.
+    /// It is needed to store a spawner with a [`HeightMap`], since the handle itself can't be stored.
+    pub height_map_path: Option<String>,
+}
+
+impl GrassChunkAsset {
+    pub fn new(spawner: GrassSpawner) -> Self {
+        GrassChunkAsset {
+            spawner,
+            height_map_path: None,
+        }
+    }
+    /// Defines the asset path of the texture of the [`HeightMap`] of the spawner
+    pub fn with_height_map_path(mut self, path: impl Into<String>) -> Self {
+        self.height_map_path = Some(path.into());
+        self
+    }
+    /// Encodes the chunk in the format of `.grass` files, storing all values as 32-bit floats.
+    ///
+    /// Panics if the spawner uses a [`HeightMap`] but no height map path is defined.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.encode(GrassEncoding::LOSSLESS)
+    }
+    /// Encodes the chunk in the format of `.grass` files using the given [`GrassEncoding`].
+    ///
+    /// Panics if the spawner uses a [`HeightMap`] but no height map path is defined.
+    pub fn encode(&self, encoding: GrassEncoding) -> Vec<u8> {
+        let payload = self.encode_payload(encoding.quantized);
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.push(encoding.to_bits());
+        if encoding.compressed {
+            bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+            bytes.extend(compress_to_vec(&payload, COMPRESSION_LEVEL));
+        } else {
+            bytes.extend(payload);
+        }
+        bytes
+    }
+    fn encode_payload(&self, quantized: bool) -> Vec<u8> {
+        let spawner = &self.spawner;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&spawner.flags.bits().to_le_bytes());
+        bytes.extend_from_slice(&(spawner.positions_xz.len() as u32).to_le_bytes());
+        let positions_xz: Vec<f32> = spawner
+            .positions_xz
+            .iter()
+            .flat_map(|xz| xz.to_array())
+            .collect();
+        write_values(&mut bytes, &positions_xz, 2, quantized);
+        if has_explicit_y(spawner.flags) {
+            write_values(&mut bytes, &spawner.positions_y, 1, quantized);
+        }
+        match &spawner.heights {
+            HeightRepresentation::Uniform(height) => {
+                bytes.push(0);
+                write_floats(&mut bytes, [*height]);
+            }
+            HeightRepresentation::PerBlade(heights) => {
+                bytes.push(1);
+                write_values(&mut bytes, heights, 1, quantized);
+            }
+        }
+        if let Some(height_map) = &spawner.height_map {
+            let path = self
+                .height_map_path
+                .as_ref()
+                .expect("A height map path is needed to store a grass chunk with a height map");
+            bytes.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(path.as_bytes());
+            write_floats(&mut bytes, [height_map.height, height_map.base_offset]);
+            bytes.push(height_map.channel.index() as u8);
+            bytes.push(match height_map.filter {
+                HeightMapFilter::Nearest => 0,
+                HeightMapFilter::Bilinear => 1,
+            });
+            match height_map.extent {
+                Some(HeightMapExtent { origin, size }) => {
+                    bytes.push(1);
+                    write_floats(&mut bytes, [origin.x, origin.y, size.x, size.y]);
+                }
+                None => bytes.push(0),
+            }
+        }
+        if spawner.flags.contains(GrassSpawnerFlags::COLOR_DEFINED) {
+            let colors: Vec<f32> = spawner
+                .colors
+                .iter()
+                .flat_map(|color| color.to_array())
+                .collect();
+            write_values(&mut bytes, &colors, 4, quantized);
+        }
+        if spawner.flags.contains(GrassSpawnerFlags::GROWTH_DELAYS) {
+            write_values(&mut bytes, &spawner.growth_delays, 1, quantized);
+        }
+        bytes
+    }
+    /// Decodes a chunk in the format of `.grass` files.
+    ///
+    /// The handle of the height map texture is loaded from the `height_map_path` by the [`GrassChunkLoader`].
+    fn from_bytes(bytes: &[u8]) -> Result<Self, bevy::asset::Error> {
+        let mut reader = ByteReader { bytes, position: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err(bevy::asset::Error::msg("Not a grass file"));
+        }
+        let encoding = match reader.u8()? {
+            // the first version only supported floats
+            1 => GrassEncoding::LOSSLESS,
+            VERSION => GrassEncoding::from_bits(reader.u8()?),
+            version => {
+                return Err(bevy::asset::Error::msg(format!(
+                    "Unsupported grass file version {version}"
+                )))
+            }
+        };
+        if encoding.compressed {
+            let length = reader.u32()? as usize;
+            let payload = decompress_to_vec_with_limit(reader.rest(), length).map_err(|error| {
+                bevy::asset::Error::msg(format!(
+                    "Invalid compressed grass file: {:?}",
+                    error.status
+                ))
+            })?;
+            Self::decode_payload(
+                &mut ByteReader {
+                    bytes: &payload,
+                    position: 0,
+                },
+                encoding.quantized,
+            )
+        } else {
+            Self::decode_payload(&mut reader, encoding.quantized)
+        }
+    }
+    fn decode_payload(
+        reader: &mut ByteReader,
+        quantized: bool,
+    ) -> Result<Self, bevy::asset::Error> {
+        let bits = reader.u32()?;
+        let flags = GrassSpawnerFlags::from_bits(bits)
+            .filter(|flags| FILE_FLAGS.contains(*flags))
+            .ok_or_else(|| {
+                bevy::asset::Error::msg(format!("Invalid grass spawner flags {bits:#x}"))
+            })?;
+        let count = reader.u32()? as usize;
+        let mut spawner = GrassSpawner::new();
+        spawner.flags = flags;
+        spawner.positions_xz = reader
+            .values(count, 2, quantized)?
+            .chunks_exact(2)
+            .map(Vec2::from_slice)
+            .collect();
+        if has_explicit_y(flags) {
+            spawner.positions_y = reader.values(count, 1, quantized)?;
+        }
+        spawner.heights = match reader.u8()? {
+            0 => HeightRepresentation::Uniform(reader.f32()?),
+            _ => HeightRepresentation::PerBlade(reader.values(count, 1, quantized)?),
+        };
+        let mut height_map_path = None;
+        if flags.contains(GrassSpawnerFlags::HEIGHT_MAP) {
+            let length = reader.u32()? as usize;
+            let path = String::from_utf8(reader.take(length)?.to_vec())?;
+            let mut height_map = HeightMap::new(Handle::default(), reader.f32()?)
+                .with_base_offset(reader.f32()?)
+                .with_channel(match reader.u8()? {
+                    0 => HeightMapChannel::Red,
+                    1 => HeightMapChannel::Green,
+                    2 => HeightMapChannel::Blue,
+                    _ => HeightMapChannel::Alpha,
+                })
+                .with_filter(match reader.u8()? {
+                    0 => HeightMapFilter::Nearest,
+                    _ => HeightMapFilter::Bilinear,
+                });
+            if reader.u8()? == 1 {
+                let extent = reader.floats(4)?;
+                let size = Vec2::new(extent[2], extent[3]);
+                if !(size.x > 0. && size.y > 0.) {
+                    return Err(bevy::asset::Error::msg("Invalid height map extent"));
+                }
+                height_map = height_map.with_extent(Vec2::new(extent[0], extent[1]), size);
+            }
+            spawner.height_map = Some(height_map);
+            height_map_path = Some(path);
+        }
+        if flags.contains(GrassSpawnerFlags::COLOR_DEFINED) {
+            spawner.colors = reader
+                .values(count, 4, quantized)?
+                .chunks_exact(4)
+                .map(Vec4::from_slice)
+                .collect();
+        }
+        if flags.contains(GrassSpawnerFlags::GROWTH_DELAYS) {
+            spawner.growth_delays = reader.values(count, 1, quantized)?;
+        }
+        // a spawner which doesn't match its flags would panic once it's prepared for rendering
+        spawner
+            .check_drawable()
+            .map_err(|error| bevy::asset::Error::msg(format!("Invalid grass chunk: {error}")))?;
+        Ok(GrassChunkAsset {
+            spawner,
+            height_map_path,
+        })
+    }
+}
+
+/// How the blades of a [`GrassChunkAsset`] are encoded in a `.grass` file.
+///
+/// Storing 10 million blades with explicit heights takes about 120 MB as floats,
+/// about 60 MB quantized and less once compressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GrassEncoding {
+    /// Stores positions, heights, colors and growth delays as 16-bit values relative to their bounds instead of 32-bit floats.
+    ///
+    /// The error of each value is at most 1/131070 of the range of the values in the chunk,
+    /// for example below 1 mm for a chunk with a size of 100 units.
+    pub quantized: bool,
+    /// Compresses the data using deflate, a LZ77 based compression.
+    pub compressed: bool,
+}
+impl GrassEncoding {
+    /// Stores all values exactly as 32-bit floats
+    pub const LOSSLESS: Self = GrassEncoding {
+        quantized: false,
+        compressed: false,
+    };
+    /// The smallest encoding, with quantized and compressed values
+    pub const COMPACT: Self = GrassEncoding {
+        quantized: true,
+        compressed: true,
+    };
+    fn to_bits(self) -> u8 {
+        self.quantized as u8 | (self.compressed as u8) << 1
+    }
+    fn from_bits(bits: u8) -> Self {
+        GrassEncoding {
+            quantized: bits & 1 != 0,
+            compressed: bits & 2 != 0,
+        }
+    }
+}
+
+/// Whether the y positions of the blades are stored explicitly instead of using a height map
+fn has_explicit_y(flags: GrassSpawnerFlags) -> bool {
+    flags.contains(GrassSpawnerFlags::Y_DEFINED) && !flags.contains(GrassSpawnerFlags::HEIGHT_MAP)
+}
+
+fn write_floats(bytes: &mut Vec<u8>, floats: impl IntoIterator<Item = f32>) {
+    bytes.extend(floats.into_iter().flat_map(f32::to_le_bytes));
+}
+
+/// Writes values made of `components` floats.
+///
+/// Quantized values are stored as 16-bit fractions between the minimum and maximum of each component.
+fn write_values(bytes: &mut Vec<u8>, values: &[f32], components: usize, quantized: bool) {
+    if !quantized {
+        write_floats(bytes, values.iter().copied());
+        return;
+    }
+    let mut min = vec![0.; components];
+    let mut max = vec![0.; components];
+    for (i, value) in values.iter().enumerate() {
+        let component = i % components;
+        if i < components {
+            (min[component], max[component]) = (*value, *value);
+        }
+        min[component] = value.min(min[component]);
+        max[component] = value.max(max[component]);
+    }
+    write_floats(bytes, min.iter().chain(max.iter()).copied());
+    for (i, value) in values.iter().enumerate() {
+        let component = i % components;
+        let range = max[component] - min[component];
+        let fraction = if range > 0. {
+            (value - min[component]) / range
+        } else {
+            0.
+        };
+        let quantized_value = (fraction * u16::MAX as f32).round() as u16;
+        bytes.extend_from_slice(&quantized_value.to_le_bytes());
+    }
+}
+
+/// Reads little endian values from a byte slice
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], bevy::asset::Error> {
+        let bytes = self
+            .bytes
+            .get(self.position..self.position + count)
+            .ok_or_else(|| bevy::asset::Error::msg("Unexpected end of grass file"))?;
+        self.position += count;
+        Ok(bytes)
+    }
+    fn u8(&mut self) -> Result<u8, bevy::asset::Error> {
+        Ok(self.take(1)?[0])
+    }
+    fn u32(&mut self) -> Result<u32, bevy::asset::Error> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+    fn f32(&mut self) -> Result<f32, bevy::asset::Error> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+    fn rest(&mut self) -> &'a [u8] {
+        let bytes = &self.bytes[self.position..];
+        self.position = self.bytes.len();
+        bytes
+    }
+    /// Reads `count` values made of `components` floats, see [`write_values`]
+    fn values(
+        &mut self,
+        count: usize,
+        components: usize,
+        quantized: bool,
+    ) -> Result<Vec<f32>, bevy::asset::Error> {
+        if !quantized {
+            return self.floats(count * components);
+        }
+        let min = self.floats(components)?;
+        let max = self.floats(components)?;
+        Ok(self
+            .take(count * components * 2)?
+            .chunks_exact(2)
+            .enumerate()
+            .map(|(i, bytes)| {
+                let component = i % components;
+                let fraction = u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32;
+                min[component] + fraction * (max[component] - min[component])
+            })
+            .collect())
+    }
+    fn floats(&mut self, count: usize) -> Result<Vec<f32>, bevy::asset::Error> {
+        Ok(self
+            .take(count * 4)?
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect())
+    }
+}
+
+/// Loads [`GrassChunkAsset`]s from `.grass` files
+#[derive(Default)]
+pub struct GrassChunkLoader;
+
+impl AssetLoader for GrassChunkLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut chunk = GrassChunkAsset::from_bytes(bytes)?;
+            let mut dependencies = Vec::new();
+            if let (Some(height_map), Some(path)) =
+                (&mut chunk.spawner.height_map, &chunk.height_map_path)
+            {
+                let path = AssetPath::from(path.as_str()).to_owned();
+                height_map.height_map = load_context.get_handle(path.clone());
+                dependencies.push(path);
+            }
+            let mut asset = LoadedAsset::new(chunk);
+            for path in dependencies {
+                asset = asset.with_dependency(path);
+            }
+            load_context.set_default_asset(asset);
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["grass"]
+    }
+}
+
+/// A component bundle for a chunk of grass loaded from a `.grass` file.
+///
+/// The [`GrassSpawner`] is added to the entity once the [`GrassChunkAsset`] is loaded.
+#[derive(Bundle)]
+pub struct GrassChunkBundle {
+    pub grass_chunk: Handle<GrassChunkAsset>,
+    /// The [`Mesh`] used to render each grassblade.
+    pub grass_mesh: Handle<Mesh>,
+    #[bundle]
+    pub spatial: SpatialBundle,
+}
+
+impl Default for GrassChunkBundle {
+    fn default() -> Self {
+        Self {
+            grass_chunk: Default::default(),
+            grass_mesh: GRASS_MESH_HANDLE.typed(),
+            spatial: Default::default(),
+        }
+    }
+}
+
+/// Inserts the [`GrassSpawner`] of loaded [`GrassChunkAsset`]s into the entities referencing them
+#[allow(clippy::type_complexity)]
+pub(crate) fn insert_grass_from_assets(
+    mut commands: Commands,
+    chunks: Res<Assets<GrassChunkAsset>>,
+    entities: Query<
+        (Entity, &Handle<GrassChunkAsset>),
+        Or<(Without<GrassSpawner>, Changed<Handle<GrassChunkAsset>>)>,
+    >,
+) {
+    for (entity, handle) in entities.iter() {
+        if let Some(chunk) = chunks.get(handle) {
+            insert_grass_chunk(&mut commands, entity, chunk);
+        }
+    }
+}
+
+/// Inserts the [`GrassSpawner`] of the chunk and its [`Aabb`](bevy::render::primitives::Aabb) into the entity
+pub(crate) fn insert_grass_chunk(commands: &mut Commands, entity: Entity, chunk: &GrassChunkAsset) {
+    let spawner = chunk.spawner.clone();
+    let aabb = spawner.calculate_aabb();
+    commands.entity(entity).insert((spawner, aabb));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The maximal error of quantized values relative to the range of the values, see [`GrassEncoding::quantized`]
+    const QUANTIZATION_ERROR: f32 = 1. / 131070.;
+
+    fn test_chunk() -> GrassChunkAsset {
+        let positions = (0..200)
+            .map(|i| {
+                let i = i as f32;
+                Vec3::new((i * 7.3) % 100., (i * 0.37).sin() * 5., (i * 13.1) % 100.)
+            })
+            .collect::<Vec<_>>();
+        let heights = (0..200).map(|i| 0.5 + (i % 17) as f32 * 0.1).collect();
+        let colors = (0..200)
+            .map(|i| Color::rgba_linear((i % 5) as f32 * 0.2, 0.8, (i % 3) as f32 * 0.3, 1.))
+            .collect();
+        let spawner = GrassSpawner::new()
+            .with_positions(positions)
+            .with_heights(heights)
+            .with_colors(colors)
+            .with_random_growth_delays(3., 0);
+        GrassChunkAsset::new(spawner)
+    }
+
+    /// Asserts that each component of the decoded values is within `error` times the range of the component
+    fn assert_close(original: &[f32], decoded: &[f32], components: usize, error: f32) {
+        assert_eq!(original.len(), decoded.len());
+        for component in 0..components {
+            let values = original.iter().skip(component).step_by(components);
+            let min = values.clone().copied().fold(f32::MAX, f32::min);
+            let max = values.copied().fold(f32::MIN, f32::max);
+            // allow for the rounding of the float operations on top of the quantization
+            let bound = (max - min) * error + max.abs().max(min.abs()) * f32::EPSILON * 4.;
+            for (a, b) in original
+                .iter()
+                .zip(decoded)
+                .skip(component)
+                .step_by(components)
+            {
+                assert!(
+                    (a - b).abs() <= bound,
+                    "{a} and {b} differ by more than {bound}"
+                );
+            }
+        }
+    }
+
+    fn assert_round_trip(encoding: GrassEncoding) {
+        let chunk = test_chunk();
+        let decoded = GrassChunkAsset::from_bytes(&chunk.encode(encoding)).unwrap();
+        let (original, decoded) = (&chunk.spawner, &decoded.spawner);
+        assert_eq!(original.flags, decoded.flags);
+        let error = if encoding.quantized {
+            QUANTIZATION_ERROR
+        } else {
+            0.
+        };
+        let flatten =
+            |values: &[Vec2]| values.iter().flat_map(|v| v.to_array()).collect::<Vec<_>>();
+        assert_close(
+            &flatten(&original.positions_xz),
+            &flatten(&decoded.positions_xz),
+            2,
+            error,
+        );
+        assert_close(&original.positions_y, &decoded.positions_y, 1, error);
+        let (
+            HeightRepresentation::PerBlade(original_heights),
+            HeightRepresentation::PerBlade(decoded_heights),
+        ) = (&original.heights, &decoded.heights)
+        else {
+            panic!("the heights should be stored per blade");
+        };
+        assert_close(original_heights, decoded_heights, 1, error);
+        let flatten =
+            |values: &[Vec4]| values.iter().flat_map(|v| v.to_array()).collect::<Vec<_>>();
+        assert_close(
+            &flatten(&original.colors),
+            &flatten(&decoded.colors),
+            4,
+            error,
+        );
+        assert_close(&original.growth_delays, &decoded.growth_delays, 1, error);
+    }
+
+    #[test]
+    fn lossless_round_trip() {
+        assert_round_trip(GrassEncoding::LOSSLESS);
+    }
+
+    #[test]
+    fn quantized_round_trip() {
+        assert_round_trip(GrassEncoding {
+            quantized: true,
+            compressed: false,
+        });
+    }
+
+    #[test]
+    fn compressed_round_trip() {
+        assert_round_trip(GrassEncoding {
+            quantized: false,
+            compressed: true,
+        });
+    }
+
+    #[test]
+    fn compact_round_trip() {
+        assert_round_trip(GrassEncoding::COMPACT);
+    }
+
+    #[test]
+    fn decodes_version_1() {
+        let bytes = include_bytes!("../assets/meadow.grass");
+        assert_eq!(bytes[4], 1);
+        let chunk = GrassChunkAsset::from_bytes(bytes).unwrap();
+        assert!(!chunk.spawner.is_empty());
+        assert!(chunk.spawner.height_map.is_some());
+        assert!(chunk.height_map_path.is_some());
+    }
+
+    #[test]
+    fn invalid_files_are_errors() {
+        let bytes = test_chunk().encode(GrassEncoding::LOSSLESS);
+        for length in 0..bytes.len() {
+            assert!(GrassChunkAsset::from_bytes(&bytes[..length]).is_err());
+        }
+        let compact = test_chunk().encode(GrassEncoding::COMPACT);
+        assert!(GrassChunkAsset::from_bytes(&compact[..compact.len() / 2]).is_err());
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(GrassChunkAsset::from_bytes(&bad_magic).is_err());
+
+        let mut unknown_version = bytes;
+        unknown_version[4] = VERSION + 1;
+        assert!(GrassChunkAsset::from_bytes(&unknown_version).is_err());
+    }
+
+    /// Replaces the flags of an uncompressed chunk
+    fn with_flags(bytes: &[u8], flags: u32) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        bytes[6..10].copy_from_slice(&flags.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn inconsistent_flags_are_errors() {
+        let bytes = test_chunk().encode(GrassEncoding::LOSSLESS);
+        let flags = test_chunk().spawner.flags;
+        assert_eq!(&bytes[6..10], &flags.bits().to_le_bytes());
+        let invalid_flags = [
+            // unknown bits
+            flags.bits() | 1 << 20,
+            (flags | GrassSpawnerFlags::DENSITY_MAP).bits(),
+            // blades without xz positions
+            (flags - GrassSpawnerFlags::XZ_DEFINED).bits(),
+            // blades without y positions or a height map
+            (flags - GrassSpawnerFlags::Y_DEFINED).bits(),
+            // heights per blade without the height flag
+            (flags - GrassSpawnerFlags::HEIGHT_DEFINED).bits(),
+        ];
+        for invalid_flags in invalid_flags {
+            let bytes = with_flags(&bytes, invalid_flags);
+            assert!(GrassChunkAsset::from_bytes(&bytes).is_err());
+        }
+
+        // an empty chunk doesn't need any positions
+        let empty = GrassChunkAsset::new(GrassSpawner::new()).to_bytes();
+        let chunk = GrassChunkAsset::from_bytes(&empty).unwrap();
+        assert!(chunk.spawner.is_empty());
+    }
+}
@@ -1,15 +1,38 @@
+use std::ops::Range;
+
 use bevy::{math::Vec3Swizzles, prelude::*, render::primitives::Aabb};
 
+use crate::exclusion::GrassExclusion;
 use crate::grass::GrassBlade;
 use crate::height_map::HeightMap;
-#[derive(Default, Component, Clone)]
+use crate::noise::FractalNoise;
+use crate::region::GrassRegion;
+use crate::scatter::{self, ScatterPattern};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+#[derive(Default, Component, Clone, Reflect, FromReflect)]
+// the serialized spawner is wrapped with its format version, see `serialization.rs`
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(remote = "Self")
+)]
+#[reflect(Component)]
 pub struct GrassSpawner {
     pub(crate) positions_xz: Vec<Vec2>,
     pub(crate) positions_y: Vec<f32>,
     pub(crate) heights: HeightRepresentation,
     pub(crate) height_map: Option<HeightMap>,
-    _density_map: Option<Handle<Image>>,
     pub(crate) flags: GrassSpawnerFlags,
+    /// The linear rgba tint of each blade
+    pub(crate) colors: Vec<Vec4>,
+    /// The seconds each blade waits before it starts growing, see [`GrassGrowth`](crate::growth::GrassGrowth)
+    pub(crate) growth_delays: Vec<f32>,
+    /// The heights of the blades before they were mowed
+    pub(crate) original_heights: Option<Vec<f32>>,
+    /// What changed since the spawner was last extracted into the render world
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[reflect(ignore)]
+    pub(crate) update: GrassUpdate,
 }
 
 impl GrassSpawner {
@@ -19,7 +42,6 @@ impl GrassSpawner {
 
     /// Defines the positions of all grass blades.
     pub fn with_positions(mut self, positions: Vec<Vec3>) -> GrassSpawner {
-        assert!(!positions.is_empty());
         let (positions_xz, positions_y) = positions
             .into_iter()
             .map(|position| (position.xz(), position.y))
@@ -37,8 +59,6 @@ impl GrassSpawner {
     /// consider using [`Self::with_positions`] instead.
     /// Just defining the xz positions allows you to also insert a heightmap
     pub fn with_positions_xz(mut self, positions_xz: Vec<Vec2>) -> GrassSpawner {
-        assert!(!positions_xz.is_empty());
-
         if self.flags.contains(GrassSpawnerFlags::XZ_DEFINED) {
             panic!("Can not insert positions_xz to `GrassSpawner` since the xz positions are already defined");
         }
@@ -55,8 +75,6 @@ impl GrassSpawner {
     /// You can only use this function or use a heightmap.
     /// Both at the same time are not supported
     pub fn with_positions_y(mut self, positions_y: Vec<f32>) -> GrassSpawner {
-        assert!(!positions_y.is_empty());
-
         if self.flags.contains(GrassSpawnerFlags::Y_DEFINED) {
             panic!("Can not insert positions_y to `GrassSpawner` since the y positions are already defined");
         }
@@ -70,7 +88,6 @@ impl GrassSpawner {
     }
     /// Defines the height of each grass blade.
     pub fn with_heights(mut self, heights: Vec<f32>) -> GrassSpawner {
-        assert!(!heights.is_empty());
         assert!(heights.iter().all(|height| *height > 0.));
         self.flags.insert(GrassSpawnerFlags::HEIGHT_DEFINED);
 
@@ -79,6 +96,26 @@ impl GrassSpawner {
         self.validate();
         self
     }
+    /// Defines the heights of all grass blades using a [`FractalNoise`].
+    ///
+    /// The heights lie between `min` and `max`.
+    /// `offset` is added to the x,z positions of the blades before sampling the noise.
+    /// Using the world position of the chunk as offset lets neighbouring chunks fit together without seams.
+    pub fn with_noise_heights(
+        self,
+        noise: &FractalNoise,
+        min: f32,
+        max: f32,
+        offset: Vec2,
+    ) -> GrassSpawner {
+        assert!(min > 0. && max >= min);
+        let heights = self
+            .positions_xz
+            .iter()
+            .map(|xz| min + noise.sample(*xz + offset) * (max - min))
+            .collect();
+        self.with_heights(heights)
+    }
     /// Defines the height of all grass blades.
     ///
     /// Every blade will have the same height
@@ -90,7 +127,8 @@ impl GrassSpawner {
     }
     /// Defines you height map for loading the y positions of your grass
     ///
-    /// Note that the heightmap texture gets stretched over the minimal [Aabb] containing all defined grass blades.
+    /// Note that the heightmap texture gets stretched over the minimal [Aabb] containing all defined grass blades,
+    /// unless an explicit extent is defined with [`HeightMap::with_extent`].
     pub fn with_height_map(mut self, height_map: HeightMap) -> GrassSpawner {
         if self.flags.contains(GrassSpawnerFlags::Y_DEFINED) {
             panic!("Can not insert height map to `GrassSpawner` since the y positions are already defined");
@@ -102,9 +140,139 @@ impl GrassSpawner {
         self.height_map = Some(height_map);
         self
     }
+    /// Creates a [`GrassSpawner`] with blades randomly placed on the surface of a [`Mesh`].
+    ///
+    /// `density` is the expected number of blades per unit of area of the mesh.
+    /// The same `seed` always yields the same blades.
+    /// The positions are in the local space of the mesh,
+    /// so the grass chunk should use the same [`Transform`] as the mesh.
+    ///
+    /// If you also need the normals of the surface, use [`scatter::scatter_on_mesh`] instead.
+    pub fn scatter_on_mesh(mesh: &Mesh, density: f32, seed: u64) -> GrassSpawner {
+        let positions = scatter::scatter_on_mesh(mesh, density, seed)
+            .into_iter()
+            .map(|point| point.position)
+            .collect();
+        GrassSpawner::new().with_positions(positions)
+    }
+    /// Creates a [`GrassSpawner`] with blades distributed in the region following the pattern.
+    ///
+    /// All blades are placed at `y = 0`.
+    /// If you want to use a [`HeightMap`] instead,
+    /// pass the positions of [`scatter::scatter_in_region`] to [`Self::with_positions_xz`].
+    pub fn scatter_in_region(
+        region: &GrassRegion,
+        pattern: ScatterPattern,
+        seed: u64,
+    ) -> GrassSpawner {
+        let positions = scatter::scatter_in_region(region, pattern, seed)
+            .into_iter()
+            .map(|xz| Vec3::new(xz.x, 0., xz.y))
+            .collect();
+        GrassSpawner::new().with_positions(positions)
+    }
+    /// Removes all blades which are placed in areas excluded by the [`GrassExclusion`].
+    ///
+    /// The `seed` decides which blades are removed in the soft edges of the exclusion.
+    pub fn with_exclusion(mut self, exclusion: &GrassExclusion, seed: u64) -> GrassSpawner {
+        let keep = exclusion.keep_mask(self.positions_xz.iter().copied(), seed);
+        self.retain_blades(&keep);
+        self
+    }
+    /// Keeps the blades for which the mask is true
+    fn retain_blades(&mut self, keep: &[bool]) {
+        retain_masked(&mut self.positions_xz, keep);
+        retain_masked(&mut self.positions_y, keep);
+        retain_masked(&mut self.colors, keep);
+        retain_masked(&mut self.growth_delays, keep);
+        if let Some(original_heights) = &mut self.original_heights {
+            retain_masked(original_heights, keep);
+        }
+        if let HeightRepresentation::PerBlade(heights) = &mut self.heights {
+            retain_masked(heights, keep);
+        }
+        self.validate();
+    }
+    /// Removes blades randomly, keeping each blade with the probability given by the [`FractalNoise`].
+    ///
+    /// This results in patches of dense and sparse grass.
+    /// `offset` is added to the x,z positions of the blades before sampling the noise.
+    /// The `seed` decides which blades are removed.
+    pub fn with_noise_density(
+        mut self,
+        noise: &FractalNoise,
+        offset: Vec2,
+        seed: u64,
+    ) -> GrassSpawner {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let keep: Vec<bool> = self
+            .positions_xz
+            .iter()
+            .map(|xz| rng.gen::<f32>() < noise.sample(*xz + offset))
+            .collect();
+        self.retain_blades(&keep);
+        self
+    }
+    /// Defines a color for each grass blade.
+    ///
+    /// The color of the blade is multiplied with the colors of the [`GrassConfiguration`](crate::GrassConfiguration).
+    pub fn with_colors(mut self, colors: Vec<Color>) -> GrassSpawner {
+        self.flags.insert(GrassSpawnerFlags::COLOR_DEFINED);
+        self.colors = colors
+            .into_iter()
+            .map(|color| Vec4::from(color.as_linear_rgba_f32()))
+            .collect();
+
+        self.validate();
+        self
+    }
+    /// Defines the colors of all grass blades by blending between `from` and `to` using a [`FractalNoise`].
+    ///
+    /// `offset` is added to the x,z positions of the blades before sampling the noise.
+    /// See [`Self::with_colors`] for how the colors are applied.
+    pub fn with_noise_colors(
+        self,
+        noise: &FractalNoise,
+        from: Color,
+        to: Color,
+        offset: Vec2,
+    ) -> GrassSpawner {
+        let from = Vec4::from(from.as_linear_rgba_f32());
+        let to = Vec4::from(to.as_linear_rgba_f32());
+        let colors = self
+            .positions_xz
+            .iter()
+            .map(|xz| {
+                let color = from.lerp(to, noise.sample(*xz + offset));
+                Color::rgba_linear(color.x, color.y, color.z, color.w)
+            })
+            .collect();
+        self.with_colors(colors)
+    }
+    /// Defines how many seconds each blade waits before it starts growing.
+    ///
+    /// The delays are only used if the chunk has a [`GrassGrowth`](crate::growth::GrassGrowth) component.
+    pub fn with_growth_delays(mut self, delays: Vec<f32>) -> GrassSpawner {
+        self.flags.insert(GrassSpawnerFlags::GROWTH_DELAYS);
+        self.growth_delays = delays;
+
+        self.validate();
+        self
+    }
+    /// Lets each blade wait a random time between zero and `max_delay` seconds before it starts growing.
+    ///
+    /// See [`Self::with_growth_delays`] for how the delays are used.
+    pub fn with_random_growth_delays(self, max_delay: f32, seed: u64) -> GrassSpawner {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let delays = self
+            .positions_xz
+            .iter()
+            .map(|_| rng.gen::<f32>() * max_delay)
+            .collect();
+        self.with_growth_delays(delays)
+    }
     /// Defines the [`GrassSpawner`] from [`GrassBlade`]s
     pub fn from_grass_blades(mut self, grass_blades: Vec<GrassBlade>) -> GrassSpawner {
-        assert!(!grass_blades.is_empty());
         let (positions, heights) = grass_blades
             .into_iter()
             .map(|blade| (blade.position, blade.height))
@@ -116,25 +284,283 @@ impl GrassSpawner {
         self
     }
     fn validate(&self) {
-        if !self.positions_xz.is_empty() && !self.positions_y.is_empty() {
-            assert_eq!(self.positions_xz.len(), self.positions_y.len());
+        if let Err(error) = self.check_lengths() {
+            panic!("{error}");
+        }
+    }
+    /// Returns an error if the per blade data defined by the flags doesn't have a value for each blade
+    fn check_lengths(&self) -> Result<(), String> {
+        // the data is only compared once the positions are defined, since empty spawners are allowed
+        if !self.flags.contains(GrassSpawnerFlags::XZ_DEFINED) {
+            return Ok(());
         }
+        let blade_count = self.positions_xz.len();
+        let check = |defined: bool, length: usize, name: &str| {
+            if defined && length != blade_count {
+                Err(format!("Expected {blade_count} {name}, but got {length}"))
+            } else {
+                Ok(())
+            }
+        };
+        let flags = self.flags;
+        check(
+            flags.contains(GrassSpawnerFlags::COLOR_DEFINED),
+            self.colors.len(),
+            "colors",
+        )?;
+        check(
+            flags.contains(GrassSpawnerFlags::GROWTH_DELAYS),
+            self.growth_delays.len(),
+            "growth delays",
+        )?;
+        check(
+            flags.contains(GrassSpawnerFlags::Y_DEFINED)
+                && !flags.contains(GrassSpawnerFlags::HEIGHT_MAP),
+            self.positions_y.len(),
+            "y positions",
+        )?;
         if let HeightRepresentation::PerBlade(heights) = &self.heights {
-            if !self.positions_y.is_empty() {
-                assert_eq!(heights.len(), self.positions_y.len());
+            check(true, heights.len(), "heights")?;
+        }
+        Ok(())
+    }
+    /// Returns an error if the spawner can't be drawn, because its data doesn't match its flags.
+    ///
+    /// Spawners built with their methods are always drawable,
+    /// so this only needs to be checked for spawners built from other data, like a `.grass` file.
+    pub(crate) fn check_drawable(&self) -> Result<(), String> {
+        self.check_lengths()?;
+        let flags = self.flags;
+        if matches!(self.heights, HeightRepresentation::PerBlade(_))
+            && !flags.contains(GrassSpawnerFlags::HEIGHT_DEFINED)
+        {
+            return Err(
+                "The heights of the blades are defined without the HEIGHT_DEFINED flag".into(),
+            );
+        }
+        if self.is_empty() {
+            return Ok(());
+        }
+        if !flags.contains(GrassSpawnerFlags::XZ_DEFINED) {
+            return Err("The blades have no xz positions".into());
+        }
+        if !flags.intersects(GrassSpawnerFlags::Y_DEFINED | GrassSpawnerFlags::HEIGHT_MAP) {
+            return Err("The blades have neither y positions nor a height map".into());
+        }
+        if flags.contains(GrassSpawnerFlags::HEIGHT_MAP) && self.height_map.is_none() {
+            return Err("The blades have no height map".into());
+        }
+        Ok(())
+    }
+    /// Adds grass blades to the spawner, for example to fill a chunk which was spawned empty.
+    ///
+    /// The y positions of the blades are ignored if the spawner uses a [`HeightMap`].
+    /// Blades added to a spawner with colors or growth delays are not tinted and don't wait before growing,
+    /// use [`Self::append_blades_with`] to define them.
+    ///
+    /// # Panics
+    /// Panics if the spawner has blades, but neither y positions nor a height map
+    pub fn append_blades(&mut self, grass_blades: impl IntoIterator<Item = GrassBlade>) {
+        self.append_blades_with(grass_blades, None, None);
+    }
+    /// Adds grass blades to the spawner together with their colors and growth delays.
+    ///
+    /// If the spawner has no colors or growth delays yet, its existing blades are not tinted and don't wait before growing.
+    /// See [`Self::append_blades`] for the other blades.
+    ///
+    /// # Panics
+    /// Panics if the spawner has blades, but neither y positions nor a height map,
+    /// or if the number of colors or growth delays differs from the number of blades.
+    pub fn append_blades_with(
+        &mut self,
+        grass_blades: impl IntoIterator<Item = GrassBlade>,
+        colors: Option<Vec<Color>>,
+        growth_delays: Option<Vec<f32>>,
+    ) {
+        let grass_blades: Vec<GrassBlade> = grass_blades.into_iter().collect();
+        // the y positions of the new blades can't be matched with the existing blades
+        if !self.is_empty()
+            && !self
+                .flags
+                .intersects(GrassSpawnerFlags::Y_DEFINED | GrassSpawnerFlags::HEIGHT_MAP)
+        {
+            panic!("Can not append blades to `GrassSpawner` since the y positions of its blades are not defined");
+        }
+        let blade_count = self.positions_xz.len();
+        if let Some(colors) = colors {
+            assert_eq!(colors.len(), grass_blades.len());
+            if !self.flags.contains(GrassSpawnerFlags::COLOR_DEFINED) {
+                self.flags.insert(GrassSpawnerFlags::COLOR_DEFINED);
+                self.colors = vec![Vec4::ONE; blade_count];
+            }
+            self.colors.extend(
+                colors
+                    .into_iter()
+                    .map(|color| Vec4::from(color.as_linear_rgba_f32())),
+            );
+        } else if self.flags.contains(GrassSpawnerFlags::COLOR_DEFINED) {
+            self.colors.extend(grass_blades.iter().map(|_| Vec4::ONE));
+        }
+        if let Some(growth_delays) = growth_delays {
+            assert_eq!(growth_delays.len(), grass_blades.len());
+            if !self.flags.contains(GrassSpawnerFlags::GROWTH_DELAYS) {
+                self.flags.insert(GrassSpawnerFlags::GROWTH_DELAYS);
+                self.growth_delays = vec![0.; blade_count];
+            }
+            self.growth_delays.extend(growth_delays);
+        } else if self.flags.contains(GrassSpawnerFlags::GROWTH_DELAYS) {
+            self.growth_delays.extend(grass_blades.iter().map(|_| 0.));
+        }
+        for blade in grass_blades {
+            if !self.flags.contains(GrassSpawnerFlags::HEIGHT_MAP) {
+                self.positions_y.push(blade.position.y);
+            }
+            if let HeightRepresentation::Uniform(height) = self.heights {
+                // blades with another height need a height per blade
+                if height != blade.height {
+                    let heights = vec![height; self.positions_xz.len()];
+                    self.heights = HeightRepresentation::PerBlade(heights);
+                }
+            }
+            if let HeightRepresentation::PerBlade(heights) = &mut self.heights {
+                heights.push(blade.height);
+            }
+            if let Some(original_heights) = &mut self.original_heights {
+                original_heights.push(blade.height);
             }
-            if !self.positions_xz.is_empty() {
-                assert_eq!(heights.len(), self.positions_xz.len());
+            self.positions_xz.push(blade.position.xz());
+        }
+        if self.positions_xz.len() == blade_count {
+            return;
+        }
+        self.flags.insert(GrassSpawnerFlags::XZ_DEFINED);
+        if !self.flags.contains(GrassSpawnerFlags::HEIGHT_MAP) {
+            self.flags.insert(GrassSpawnerFlags::Y_DEFINED);
+        }
+        if let HeightRepresentation::PerBlade(_) = self.heights {
+            self.flags.insert(GrassSpawnerFlags::HEIGHT_DEFINED);
+        }
+        self.update = GrassUpdate::Full;
+        self.validate();
+    }
+    /// The number of grass blades of the spawner
+    pub fn blade_count(&self) -> usize {
+        self.positions_xz.len()
+    }
+    /// Returns true if the spawner has no grass blades, so nothing is drawn
+    pub fn is_empty(&self) -> bool {
+        self.positions_xz.is_empty()
+    }
+    /// Cuts all grass blades inside the region down to the `cut_height`.
+    ///
+    /// Blades which are already smaller than the `cut_height` are not changed.
+    /// The original heights of the blades are remembered,
+    /// so a [`GrassRegrowth`] component can let the grass grow back.
+    pub fn mow(&mut self, region: &GrassRegion, cut_height: f32) {
+        assert!(cut_height >= 0.);
+        if let HeightRepresentation::Uniform(height) = self.heights {
+            // mowing needs a height per blade, so all heights have to be uploaded again
+            self.heights = HeightRepresentation::PerBlade(vec![height; self.positions_xz.len()]);
+            self.flags.insert(GrassSpawnerFlags::HEIGHT_DEFINED);
+            self.update = GrassUpdate::Full;
+        }
+        let HeightRepresentation::PerBlade(heights) = &mut self.heights else {
+            unreachable!()
+        };
+        let original_heights = self.original_heights.get_or_insert_with(|| heights.clone());
+        let mut changed: Option<Range<usize>> = None;
+        for (i, (xz, height)) in self.positions_xz.iter().zip(heights.iter_mut()).enumerate() {
+            if *height > cut_height && region.contains(*xz) {
+                *height = cut_height;
+                changed = Some(changed.map_or(i..i + 1, |range| range.start..i + 1));
             }
         }
+        if changed.is_none() && *original_heights == *heights {
+            self.original_heights = None;
+        }
+        match changed {
+            Some(range) => self.update.add_heights(range),
+            None if self.update == GrassUpdate::None => self.update = GrassUpdate::Unchanged,
+            None => {}
+        }
     }
+    /// Lets mowed grass blades grow back to their original height.
+    ///
+    /// Returns false if all blades have their original height.
+    pub(crate) fn regrow(&mut self, growth: f32) -> bool {
+        let (Some(original_heights), HeightRepresentation::PerBlade(heights)) =
+            (&self.original_heights, &mut self.heights)
+        else {
+            return false;
+        };
+        let mut changed: Option<Range<usize>> = None;
+        for (i, (height, original)) in heights.iter_mut().zip(original_heights).enumerate() {
+            if *height < *original {
+                *height = (*height + growth).min(*original);
+                changed = Some(changed.map_or(i..i + 1, |range| range.start..i + 1));
+            }
+        }
+        match changed {
+            Some(range) => {
+                self.update.add_heights(range);
+                true
+            }
+            None => {
+                self.original_heights = None;
+                false
+            }
+        }
+    }
+    /// Calculates the y position of the ground at the given x,z position on the CPU.
+    ///
+    /// The result matches the y position the grass blades get from the [`HeightMap`] in the shader.
+    /// The position and the result are in the local space of the grass chunk.
+    /// The `aabb` should be the [`Aabb`] component of the grass chunk.
+    ///
+    /// Returns `None` if the [`GrassSpawner`] has no [`HeightMap`] or its texture isn't loaded yet.
+    pub fn sample_ground_height(
+        &self,
+        images: &Assets<Image>,
+        aabb: &Aabb,
+        xz: Vec2,
+    ) -> Option<f32> {
+        self.height_map.as_ref()?.sample(images, aabb, xz)
+    }
+    /// Calculates the world position of the ground below the given world position on the CPU.
+    ///
+    /// Unlike [`Self::sample_ground_height`], this works for moved, rotated and scaled grass chunks,
+    /// since the ground is searched along the local y axis of the chunk.
+    /// The `transform` should be the [`GlobalTransform`] of the grass chunk.
+    ///
+    /// Returns `None` if the [`GrassSpawner`] has no [`HeightMap`] or its texture isn't loaded yet.
+    pub fn sample_ground_position(
+        &self,
+        images: &Assets<Image>,
+        aabb: &Aabb,
+        transform: &GlobalTransform,
+        world_position: Vec3,
+    ) -> Option<Vec3> {
+        let local_position = transform
+            .affine()
+            .inverse()
+            .transform_point3(world_position);
+        let height = self.sample_ground_height(images, aabb, local_position.xz())?;
+        Some(transform.transform_point(Vec3::new(local_position.x, height, local_position.z)))
+    }
+    /// Calculates the [`Aabb`] containing all grass blades.
+    ///
+    /// Empty spawners get a default [`Aabb`] without any size.
     pub fn calculate_aabb(&self) -> Aabb {
+        if self.is_empty() {
+            return Aabb::default();
+        }
         let mut outer = Vec3::new(f32::MIN, f32::MIN, f32::MIN);
         let mut inner = Vec3::new(f32::MAX, f32::MAX, f32::MAX);
         if self.flags.contains(GrassSpawnerFlags::HEIGHT_MAP) {
-            let height = self.height_map.as_ref().unwrap().height;
+            let height_map = self.height_map.as_ref().unwrap();
+            let height = height_map.height;
             self.positions_xz.iter().for_each(|xz| {
-                let blade_pos = Vec3::new(xz.x, 0., xz.y);
+                let blade_pos = Vec3::new(xz.x, height_map.base_offset, xz.y);
                 inner = inner.min(blade_pos);
                 outer = outer.max(blade_pos + Vec3::Y * height);
             });
@@ -152,7 +578,15 @@ impl GrassSpawner {
         Aabb::from_min_max(inner, outer)
     }
 }
+/// Keeps the values for which the mask is true. Values which aren't defined for every blade are not changed
+fn retain_masked<T>(values: &mut Vec<T>, keep: &[bool]) {
+    if values.len() == keep.len() {
+        let mut keep = keep.iter();
+        values.retain(|_| *keep.next().unwrap());
+    }
+}
 bitflags::bitflags! {
+    #[derive(Reflect, FromReflect)]
     #[repr(transparent)]
     pub struct GrassSpawnerFlags: u32 {
         const Y_DEFINED      = (1 << 0);
@@ -160,6 +594,8 @@ bitflags::bitflags! {
         const HEIGHT_DEFINED = (1 << 2);
         const HEIGHT_MAP     = (1 << 3);
         const DENSITY_MAP    = (1 << 4);
+        const COLOR_DEFINED  = (1 << 5);
+        const GROWTH_DELAYS  = (1 << 6);
         const NONE           = 0;
         const UNINITIALIZED  = 0xFFFF;
     }
@@ -169,7 +605,8 @@ impl Default for GrassSpawnerFlags {
         Self::NONE
     }
 }
-#[derive(Clone)]
+#[derive(Clone, Reflect, FromReflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HeightRepresentation {
     PerBlade(Vec<f32>),
     Uniform(f32),
@@ -179,12 +616,195 @@ impl Default for HeightRepresentation {
         HeightRepresentation::Uniform(1.)
     }
 }
+/// Describes which data of a [`GrassSpawner`] changed since it was last extracted into the render world.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) enum GrassUpdate {
+    /// The spawner wasn't modified by any of its methods.
+    ///
+    /// If the spawner changed anyway, for example through reflection, all data needs to be prepared again
+    None,
+    /// The spawner was modified by one of its methods, but none of the blades changed
+    Unchanged,
+    /// Only the heights of the blades in the range changed,
+    /// so the render world only has to upload those again
+    Heights(Range<usize>),
+    /// All data needs to be prepared again
+    #[default]
+    Full,
+}
+impl GrassUpdate {
+    fn add_heights(&mut self, range: Range<usize>) {
+        *self = match self {
+            GrassUpdate::None | GrassUpdate::Unchanged => GrassUpdate::Heights(range),
+            GrassUpdate::Heights(old) => {
+                GrassUpdate::Heights(old.start.min(range.start)..old.end.max(range.end))
+            }
+            GrassUpdate::Full => GrassUpdate::Full,
+        };
+    }
+    /// Whether all data of a changed spawner needs to be prepared again
+    pub(crate) fn is_full(&self) -> bool {
+        matches!(self, GrassUpdate::None | GrassUpdate::Full)
+    }
+}
+/// A component which lets mowed grass grow back to its original height.
+///
+/// Grass can be mowed using [`GrassSpawner::mow`]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct GrassRegrowth {
+    /// How much the blades grow each second
+    pub speed: f32,
+}
+impl Default for GrassRegrowth {
+    fn default() -> Self {
+        GrassRegrowth { speed: 0.1 }
+    }
+}
+pub(crate) fn regrow_grass(
+    time: Res<Time>,
+    mut grasses: Query<(&mut GrassSpawner, &GrassRegrowth)>,
+) {
+    for (mut spawner, regrowth) in grasses.iter_mut() {
+        // avoids triggering change detection if nothing grows
+        if spawner.original_heights.is_none() {
+            continue;
+        }
+        let growth = regrowth.speed * time.delta_seconds();
+        if !spawner.bypass_change_detection().regrow(growth) {
+            continue;
+        }
+        spawner.set_changed();
+    }
+}
+/// Resets the tracked changes of all [`GrassSpawner`]s, after they were extracted into the render world.
+pub(crate) fn clear_grass_updates(mut grasses: Query<&mut GrassSpawner>) {
+    for mut spawner in grasses.iter_mut() {
+        if spawner.update != GrassUpdate::None {
+            spawner.bypass_change_detection().update = GrassUpdate::None;
+        }
+    }
+}
+/// Inserts an [`Aabb`] into new grass chunks and updates it if the blades of a chunk changed.
+///
+/// An [`Aabb`] inserted together with the [`GrassSpawner`] is kept until the blades change.
+#[allow(clippy::type_complexity)]
 pub(crate) fn add_aabb_box_to_grass(
     mut commands: Commands,
-    grasses: Query<(Entity, &GrassSpawner), Without<Aabb>>,
+    grasses: Query<
+        (Entity, Ref<GrassSpawner>, Option<&Aabb>),
+        Or<(Changed<GrassSpawner>, Without<Aabb>)>,
+    >,
 ) {
-    for (e, spawner) in grasses.iter() {
-        let aabb = spawner.calculate_aabb();
-        commands.entity(e).insert(aabb);
+    for (e, spawner, aabb) in grasses.iter() {
+        let blades_changed = !spawner.is_added() && spawner.update.is_full();
+        if aabb.is_some() && !blades_changed {
+            continue;
+        }
+        let new_aabb = spawner.calculate_aabb();
+        // avoids preparing the chunk again if the aabb stays the same
+        if let Some(aabb) = aabb {
+            if aabb.center == new_aabb.center && aabb.half_extents == new_aabb.half_extents {
+                continue;
+            }
+        }
+        commands.entity(e).insert(new_aabb);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+    #[test]
+    fn filtered_blades_regrow_to_their_own_heights() {
+        let blades = (0..4)
+            .map(|i| GrassBlade {
+                position: Vec3::new(i as f32, 0., 0.),
+                height: i as f32 + 1.,
+            })
+            .collect();
+        let mut spawner = GrassSpawner::new().from_grass_blades(blades);
+        let everything = GrassRegion::Rect {
+            min: Vec2::splat(-10.),
+            max: Vec2::splat(10.),
+        };
+        spawner.mow(&everything, 0.5);
+        let exclusion = GrassExclusion::new().with_region(GrassRegion::Rect {
+            min: Vec2::new(-0.5, -1.),
+            max: Vec2::new(1.5, 1.),
+        });
+        let mut spawner = spawner.with_exclusion(&exclusion, 0);
+        assert_eq!(
+            spawner.positions_xz,
+            vec![Vec2::new(2., 0.), Vec2::new(3., 0.)]
+        );
+
+        while spawner.regrow(1.) {}
+        let HeightRepresentation::PerBlade(heights) = &spawner.heights else {
+            panic!("mowed blades have a height per blade");
+        };
+        assert_eq!(*heights, vec![3., 4.]);
+    }
+
+    #[test]
+    fn appended_blades_keep_their_colors_and_delays() {
+        let blade = |x: f32| GrassBlade {
+            position: Vec3::new(x, 0., 0.),
+            height: 1.,
+        };
+        let mut spawner = GrassSpawner::new().from_grass_blades(vec![blade(0.)]);
+        spawner.append_blades_with([blade(1.)], Some(vec![Color::BLACK]), None);
+        spawner.append_blades_with([blade(2.)], None, Some(vec![2.]));
+        assert_eq!(
+            spawner.colors,
+            vec![Vec4::ONE, Vec4::new(0., 0., 0., 1.), Vec4::ONE]
+        );
+        assert_eq!(spawner.growth_delays, vec![0., 0., 2.]);
+        assert!(spawner.check_drawable().is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "y positions")]
+    fn appending_blades_without_y_positions_panics() {
+        let mut spawner = GrassSpawner::new().with_positions_xz(vec![Vec2::ZERO]);
+        spawner.append_blades([GrassBlade::default()]);
+    }
+
+    #[test]
+    fn ground_positions_follow_the_transform_of_the_chunk() {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default()).add_asset::<Image>();
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        let texture = images.add(Image::new(
+            Extent3d {
+                width: 2,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            bytemuck::cast_slice(&[1f32, 2., 3., 4.]).to_vec(),
+            TextureFormat::R32Float,
+        ));
+        let height_map = HeightMap::new(texture, 1.).with_extent(Vec2::ZERO, Vec2::splat(2.));
+        let spawner = GrassSpawner::new().with_height_map(height_map);
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(1., 2., 3.)
+                .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.3, 0.8, -0.4))
+                .with_scale(Vec3::new(2., 0.5, 3.)),
+        );
+        let images = app.world.resource::<Assets<Image>>();
+        let aabb = Aabb::default();
+        // a position above the texel with the value 3, in the local space of the chunk
+        let world_position = transform.transform_point(Vec3::new(0.5, 10., 1.5));
+        let ground = spawner
+            .sample_ground_position(images, &aabb, &transform, world_position)
+            .unwrap();
+        let expected = transform.transform_point(Vec3::new(0.5, 3., 1.5));
+        assert!(ground.abs_diff_eq(expected, 1e-4), "{ground} != {expected}");
+        // the ground lies on the local y axis of the chunk below the position
+        let local_ground = transform.affine().inverse().transform_point3(ground);
+        assert!(local_ground.abs_diff_eq(Vec3::new(0.5, 3., 1.5), 1e-4));
     }
 }
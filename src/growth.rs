@@ -0,0 +1,132 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::grass_spawner::GrassSpawner;
+
+/// The curve describing how a blade grows from zero to its full height
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, FromReflect)]
+pub enum GrowthEasing {
+    /// Grows with a constant speed
+    Linear,
+    /// Starts slow and speeds up
+    EaseIn,
+    /// Starts fast and slows down towards the full height
+    #[default]
+    EaseOut,
+    /// Starts and ends slow
+    EaseInOut,
+    /// Grows slightly above the full height before settling
+    Overshoot,
+}
+impl GrowthEasing {
+    /// Maps the linear progress `t` from 0 to 1 to the eased progress, exactly like the shader does
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            GrowthEasing::Linear => t,
+            GrowthEasing::EaseIn => t * t,
+            GrowthEasing::EaseOut => 1. - (1. - t) * (1. - t),
+            GrowthEasing::EaseInOut => t * t * (3. - 2. * t),
+            GrowthEasing::Overshoot => {
+                let s = t - 1.;
+                1. + s * s * (OVERSHOOT + 1.) * s + s * s * OVERSHOOT
+            }
+        }
+    }
+    /// The index used by the shader to select the easing
+    pub(crate) fn index(self) -> u32 {
+        match self {
+            GrowthEasing::Linear => 0,
+            GrowthEasing::EaseIn => 1,
+            GrowthEasing::EaseOut => 2,
+            GrowthEasing::EaseInOut => 3,
+            GrowthEasing::Overshoot => 4,
+        }
+    }
+}
+/// How far the [`GrowthEasing::Overshoot`] curve grows above the full height
+const OVERSHOOT: f32 = 1.70158;
+
+/// A component which lets the blades of a grass chunk grow from zero to their full height.
+///
+/// The growth is animated in the shader, so it doesn't change the [`GrassSpawner`].
+/// Each blade starts growing after the delay set by [`GrassSpawner::with_growth_delays`],
+/// which allows blades to sprout one after another.
+/// The elapsed time is advanced by the virtual [`Time`] until all blades are fully grown.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct GrassGrowth {
+    /// The seconds since the chunk started growing.
+    ///
+    /// Negative values delay the growth of the whole chunk
+    pub elapsed: f32,
+    /// The seconds a blade needs to grow to its full height
+    pub duration: f32,
+    pub easing: GrowthEasing,
+}
+impl Default for GrassGrowth {
+    fn default() -> Self {
+        GrassGrowth {
+            elapsed: 0.,
+            duration: 1.,
+            easing: GrowthEasing::default(),
+        }
+    }
+}
+impl GrassGrowth {
+    pub fn new(duration: f32) -> Self {
+        GrassGrowth {
+            duration,
+            ..default()
+        }
+    }
+    /// Delays the start of the growth of the whole chunk by the seconds
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.elapsed = -delay;
+        self
+    }
+    pub fn with_easing(mut self, easing: GrowthEasing) -> Self {
+        self.easing = easing;
+        self
+    }
+    /// Returns how far a blade with the growth delay has grown, from 0 to 1.
+    ///
+    /// This matches the scale of the blade in the shader.
+    pub fn progress(&self, blade_delay: f32) -> f32 {
+        let elapsed = self.elapsed - blade_delay;
+        if self.duration <= 0. {
+            return if elapsed >= 0. { 1. } else { 0. };
+        }
+        self.easing.ease(elapsed / self.duration)
+    }
+    /// Returns true if a blade with the growth delay has reached its full height
+    pub fn is_finished(&self, blade_delay: f32) -> bool {
+        self.elapsed >= blade_delay + self.duration.max(0.)
+    }
+}
+
+pub(crate) fn update_grass_growth(
+    time: Res<Time>,
+    mut grasses: Query<(Entity, &mut GrassGrowth, Ref<GrassSpawner>)>,
+    mut removed: RemovedComponents<GrassGrowth>,
+    mut max_delays: Local<HashMap<Entity, f32>>,
+) {
+    for entity in removed.iter() {
+        max_delays.remove(&entity);
+    }
+    for (entity, mut growth, spawner) in grasses.iter_mut() {
+        // the largest delay is only searched again if the blades changed
+        let max_delay = match max_delays.get(&entity) {
+            Some(max_delay) if !spawner.is_changed() => *max_delay,
+            _ => {
+                let max_delay = spawner.growth_delays.iter().copied().fold(0., f32::max);
+                max_delays.insert(entity, max_delay);
+                max_delay
+            }
+        };
+        // avoids triggering change detection once all blades are grown
+        if growth.is_finished(max_delay) {
+            continue;
+        }
+        growth.elapsed += time.delta_seconds();
+    }
+}
@@ -1,7 +1,267 @@
-use bevy::prelude::*;
+use bevy::{
+    math::Vec3Swizzles,
+    prelude::*,
+    render::{primitives::Aabb, render_resource::TextureFormat},
+};
 
-#[derive(Reflect, Clone)]
+use crate::grass_spawner::GrassSpawner;
+
+/// A texture defining the y positions of grass blades.
+///
+/// The selected channel of the texture is multiplied with the `height` and added to the `base_offset` to get the y position.
+///
+/// Besides 8-bit textures, 16-bit textures (like 16-bit PNGs) and float textures (like `R32Float` or EXR files) are supported,
+/// which avoid visible steps on tall terrain.
+/// 16-bit integer textures are converted to float textures when they are loaded.
+#[derive(Reflect, FromReflect, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeightMap {
+    /// The texture storing the heights.
+    ///
+    /// The texture is not serialized, so it needs to be set again after deserializing the height map.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub height_map: Handle<Image>,
+    /// The difference in y between texels with a value of `0` and texels with a value of `1`.
     pub height: f32,
+    /// The y position of texels with a value of `0`.
+    pub base_offset: f32,
+    /// The channel of the texture which contains the heights.
+    pub channel: HeightMapChannel,
+    /// The area on the x,z plane the height map covers, in the local space of the grass chunk.
+    ///
+    /// If `None`, the texture gets stretched over the [`Aabb`](bevy::render::primitives::Aabb) of the grass blades.
+    pub extent: Option<HeightMapExtent>,
+    /// How the texture is sampled between the texels.
+    pub filter: HeightMapFilter,
+}
+
+impl HeightMap {
+    /// Creates a new [`HeightMap`] stretched over the [`Aabb`](bevy::render::primitives::Aabb) of the grass blades.
+    pub fn new(height_map: Handle<Image>, height: f32) -> Self {
+        HeightMap {
+            height_map,
+            height,
+            base_offset: 0.,
+            channel: HeightMapChannel::default(),
+            extent: None,
+            filter: HeightMapFilter::default(),
+        }
+    }
+    /// Places the height map explicitly in the local space of the grass chunk.
+    ///
+    /// `origin` is the position of the first texel on the x,z plane,
+    /// `size` the extent of the whole texture.
+    /// Blades outside of the area use the height of the closest edge.
+    pub fn with_extent(mut self, origin: Vec2, size: Vec2) -> Self {
+        assert!(size.x > 0. && size.y > 0.);
+        self.extent = Some(HeightMapExtent { origin, size });
+        self
+    }
+    /// Defines the y position of texels with a value of `0`.
+    pub fn with_base_offset(mut self, base_offset: f32) -> Self {
+        self.base_offset = base_offset;
+        self
+    }
+    /// Defines the channel of the texture which contains the heights.
+    pub fn with_channel(mut self, channel: HeightMapChannel) -> Self {
+        self.channel = channel;
+        self
+    }
+    /// Defines how the texture is sampled between the texels.
+    pub fn with_filter(mut self, filter: HeightMapFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+    /// Returns the area covered by the height map for grass blades contained by the given [`Aabb`]
+    pub fn extent_in(&self, aabb: &Aabb) -> HeightMapExtent {
+        self.extent.unwrap_or_else(|| HeightMapExtent {
+            origin: aabb.min().xz(),
+            size: (aabb.half_extents.xz() * 2.).max(Vec2::splat(f32::EPSILON)),
+        })
+    }
+    /// Calculates the y position of a grass blade at the given x,z position on the CPU.
+    ///
+    /// The result is exactly the y position calculated for grass blades in the shader,
+    /// so it can be used to place objects on the same ground as the grass.
+    /// The `aabb` should be the [`Aabb`] component of the grass chunk,
+    /// which is only used if the height map has no explicit extent.
+    ///
+    /// Returns `None` if the texture isn't loaded yet or its format is not supported.
+    pub fn sample(&self, images: &Assets<Image>, aabb: &Aabb, xz: Vec2) -> Option<f32> {
+        let image = images.get(&self.height_map)?;
+        let dim = image.size();
+        let channel = self.channel.index();
+        let texel = |position: IVec2| {
+            let position = position.clamp(IVec2::ZERO, dim.as_ivec2() - IVec2::ONE);
+            texel_value(image, position.as_uvec2(), channel)
+        };
+        let HeightMapExtent { origin, size } = self.extent_in(aabb);
+        let uv = ((xz - origin) / size).clamp(Vec2::ZERO, Vec2::ONE);
+        let value = match self.filter {
+            HeightMapFilter::Nearest => texel((uv * dim).as_ivec2())?,
+            HeightMapFilter::Bilinear => {
+                // texel values are defined at the center of the texels
+                let texel_position = uv * dim - 0.5;
+                let base = texel_position.floor();
+                let t = texel_position - base;
+                let p = base.as_ivec2();
+                let bottom = mix(texel(p)?, texel(p + IVec2::X)?, t.x);
+                let top = mix(texel(p + IVec2::Y)?, texel(p + IVec2::ONE)?, t.x);
+                mix(bottom, top, t.y)
+            }
+        };
+        Some(self.base_offset + value * self.height)
+    }
+}
+
+/// Linear interpolation as defined by the `mix` function of wgsl
+fn mix(a: f32, b: f32, t: f32) -> f32 {
+    a * (1. - t) + b * t
+}
+
+/// Reads one channel of a texel the same way the shader does.
+///
+/// Missing channels are 0, except for the alpha channel which is 1.
+/// Srgb textures are converted to linear values.
+pub(crate) fn texel_value(image: &Image, position: UVec2, channel: usize) -> Option<f32> {
+    let format = image.texture_descriptor.format;
+    let (channels, channel_size) = match format {
+        TextureFormat::R8Unorm => (1, 1),
+        TextureFormat::Rg8Unorm => (2, 1),
+        TextureFormat::Rgba8Unorm
+        | TextureFormat::Rgba8UnormSrgb
+        | TextureFormat::Bgra8Unorm
+        | TextureFormat::Bgra8UnormSrgb => (4, 1),
+        TextureFormat::R16Unorm | TextureFormat::R16Float => (1, 2),
+        TextureFormat::Rg16Unorm | TextureFormat::Rg16Float => (2, 2),
+        TextureFormat::Rgba16Unorm | TextureFormat::Rgba16Float => (4, 2),
+        TextureFormat::R32Float => (1, 4),
+        TextureFormat::Rg32Float => (2, 4),
+        TextureFormat::Rgba32Float => (4, 4),
+        _ => return None,
+    };
+    if channel >= channels {
+        return Some(if channel == 3 { 1. } else { 0. });
+    }
+    // bgra textures are swizzled when read by the shader
+    let stored_channel = match format {
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb if channel < 3 => 2 - channel,
+        _ => channel,
+    };
+    let width = image.texture_descriptor.size.width as usize;
+    let index = ((position.y as usize * width + position.x as usize) * channels + stored_channel)
+        * channel_size;
+    let bytes = image.data.get(index..index + channel_size)?;
+    let value = match format {
+        TextureFormat::Rgba8UnormSrgb | TextureFormat::Bgra8UnormSrgb if channel < 3 => {
+            srgb_to_linear(bytes[0] as f32 / u8::MAX as f32)
+        }
+        TextureFormat::R16Float | TextureFormat::Rg16Float | TextureFormat::Rgba16Float => {
+            f16_to_f32(u16::from_ne_bytes([bytes[0], bytes[1]]))
+        }
+        _ => match channel_size {
+            1 => bytes[0] as f32 / u8::MAX as f32,
+            2 => u16::from_ne_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32,
+            _ => f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        },
+    };
+    Some(value)
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1. } else { 1. };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0. => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1. + mantissa / 1024.) * 2f32.powi(exponent - 15),
+    }
+}
+
+/// The area covered by a [`HeightMap`] on the x,z plane
+#[derive(Reflect, FromReflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeightMapExtent {
+    /// The position of the corner with the lowest x and z coordinates
+    pub origin: Vec2,
+    /// The size of the area
+    pub size: Vec2,
+}
+
+/// How a [`HeightMap`] is sampled between texels.
+#[derive(Reflect, FromReflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HeightMapFilter {
+    /// Uses the value of the closest texel.
+    #[default]
+    Nearest,
+    /// Interpolates between the four closest texels, which avoids terraces on steep terrain.
+    Bilinear,
+}
+
+/// The channel of a [`HeightMap`] texture which contains the heights.
+#[derive(Reflect, FromReflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HeightMapChannel {
+    #[default]
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+impl HeightMapChannel {
+    /// The index of the channel in a texel
+    pub(crate) fn index(self) -> usize {
+        match self {
+            HeightMapChannel::Red => 0,
+            HeightMapChannel::Green => 1,
+            HeightMapChannel::Blue => 2,
+            HeightMapChannel::Alpha => 3,
+        }
+    }
+}
+
+/// Converts height maps with 16-bit integer formats to float formats.
+///
+/// 16-bit PNGs are loaded by bevy with unsigned integer formats, which can't be read as float textures by the grass shader.
+/// Also the `R16Unorm` format isn't supported by all devices.
+pub(crate) fn convert_height_map_formats(
+    spawners: Query<&GrassSpawner>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for height_map in spawners
+        .iter()
+        .filter_map(|spawner| spawner.height_map.as_ref())
+    {
+        let Some(format) = images
+            .get(&height_map.height_map)
+            .map(|image| image.texture_descriptor.format)
+        else {
+            continue;
+        };
+        let float_format = match format {
+            TextureFormat::R16Uint | TextureFormat::R16Unorm => TextureFormat::R32Float,
+            TextureFormat::Rg16Uint | TextureFormat::Rg16Unorm => TextureFormat::Rg32Float,
+            TextureFormat::Rgba16Uint | TextureFormat::Rgba16Unorm => TextureFormat::Rgba32Float,
+            _ => continue,
+        };
+        let image = images.get_mut(&height_map.height_map).unwrap();
+        image.data = image
+            .data
+            .chunks_exact(2)
+            .map(|bytes| u16::from_ne_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32)
+            .flat_map(f32::to_ne_bytes)
+            .collect();
+        image.texture_descriptor.format = float_format;
+    }
 }
@@ -1,7 +1,17 @@
+//! Updates the grass when any of its inputs change on disk.
+//!
+//! The wind noise texture, the trail and state maps, the coverage mask and custom blade meshes
+//! don't need to be handled here, since the grass is always drawn using the latest version of those assets.
+//! Densities and colors are baked into the [`GrassSpawner`],
+//! so they are reloaded together with the [`GrassChunkAsset`] storing them.
 use bevy::prelude::*;
 
-use crate::grass_spawner::GrassSpawner;
+use crate::{
+    grass_asset::{insert_grass_chunk, GrassChunkAsset},
+    grass_spawner::{GrassSpawner, GrassUpdate},
+};
 
+/// Prepares grass chunks again if their height map was modified
 pub(crate) fn hot_reload_height_map(
     mut ev_asset: EventReader<AssetEvent<Image>>,
     mut spawner_q: Query<&mut GrassSpawner>,
@@ -9,13 +19,33 @@ pub(crate) fn hot_reload_height_map(
     for ev in ev_asset.iter() {
         if let AssetEvent::Modified { handle } = ev {
             for mut spawner in spawner_q.iter_mut() {
-                if let Some(height_map) = &spawner.height_map {
-                    if height_map.height_map == handle.clone() {
-                        spawner.set_changed();
-                        continue;
-                    }
+                let height_map_modified = spawner
+                    .height_map
+                    .as_ref()
+                    .is_some_and(|height_map| height_map.height_map == *handle);
+                if height_map_modified {
+                    spawner.update = GrassUpdate::Full;
                 }
             }
         }
     }
 }
+
+/// Replaces the [`GrassSpawner`] of entities whose [`GrassChunkAsset`] was modified
+pub(crate) fn hot_reload_grass_chunks(
+    mut commands: Commands,
+    mut ev_asset: EventReader<AssetEvent<GrassChunkAsset>>,
+    chunks: Res<Assets<GrassChunkAsset>>,
+    entities: Query<(Entity, &Handle<GrassChunkAsset>)>,
+) {
+    for ev in ev_asset.iter() {
+        if let AssetEvent::Modified { handle } = ev {
+            let Some(chunk) = chunks.get(handle) else {
+                continue;
+            };
+            for (entity, _) in entities.iter().filter(|(_, other)| *other == handle) {
+                insert_grass_chunk(&mut commands, entity, chunk);
+            }
+        }
+    }
+}
@@ -6,15 +6,37 @@ use bevy::{
     },
 };
 
+pub mod clock;
+pub mod coverage;
+pub mod debug;
+pub mod diagnostic;
+pub mod exclusion;
 pub mod grass;
+pub mod grass_asset;
 pub mod grass_spawner;
+pub mod growth;
 pub mod height_map;
 pub mod hot_reloading;
+pub mod material;
+pub mod noise;
+pub mod region;
 mod render;
+pub mod scatter;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod state_map;
+pub mod trail_map;
+pub mod wind;
+use coverage::{GrassCoverage, GrassCoverageMask};
 use grass_spawner::GrassSpawner;
 use warblers_plugin::GRASS_MESH_HANDLE;
 pub mod warblers_plugin;
 pub mod prelude {
+    pub use crate::clock::{GrassClock, GrassClockSource};
+    pub use crate::coverage::{GrassCoverage, GrassCoverageMask};
+    pub use crate::growth::{GrassGrowth, GrowthEasing};
+    pub use crate::state_map::{GrassState, GrassStateMap};
+    pub use crate::trail_map::{GrassDisplacer, GrassTrailMap};
     pub use crate::warblers_plugin::WarblersPlugin;
     pub use crate::GrassConfiguration;
     pub use crate::WarblersBundle;
@@ -48,8 +70,13 @@ impl Default for WarblersBundle {
 /// A [resource](bevy::prelude::Resource) used to globally define parameters about the grass.
 ///
 /// A default [`GrassConfiguration`] is inserted by the [`WarblersPlugin`](crate::warblers_plugin::WarblersPlugin).
-#[cfg_attr(feature = "debug", derive(InspectorOptions))]
 #[derive(Resource, Clone, Reflect, ExtractResource)]
+// the serialized configuration is wrapped with its format version, see `serialization.rs`
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(remote = "Self")
+)]
 #[reflect(Resource)]
 pub struct GrassConfiguration {
     /// The main [Color] of the grass used in your game.
@@ -70,7 +97,18 @@ pub struct GrassConfiguration {
     /// Most likely you don't need to change that unless you want your wind to feel different.
     /// If you decide to swap it, note that you want the texture to be tileable,
     /// also currently only the red and green chanel are used
+    ///
+    /// The texture is not serialized, so it needs to be set again after deserializing the configuration.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub wind_noise_texture: Handle<Image>,
+    /// Covers the upper part of the blades, for example with snow.
+    ///
+    /// Chunks with a [`GrassCoverage`] component use their own coverage instead
+    pub coverage: GrassCoverage,
+    /// Limits where the grass is covered.
+    ///
+    /// Chunks with a [`GrassCoverageMask`] component use their own mask instead
+    pub coverage_mask: Option<GrassCoverageMask>,
 }
 impl FromWorld for GrassConfiguration {
     fn from_world(world: &mut World) -> Self {
@@ -87,6 +125,8 @@ impl FromWorld for GrassConfiguration {
             bottom_color: Color::rgb(0.1, 0.1, 0.0),
             wind: Vec2::new(0., 1.0),
             wind_noise_texture: images.add(img),
+            coverage: GrassCoverage::default(),
+            coverage_mask: None,
         }
     }
 }
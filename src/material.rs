@@ -0,0 +1,447 @@
+use std::{hash::Hash, marker::PhantomData, sync::atomic::Ordering};
+
+use bevy::{
+    core_pipeline::core_3d::Opaque3d,
+    ecs::system::{
+        lifetimeless::{Read, SRes},
+        SystemParamItem,
+    },
+    pbr::{MeshPipelineKey, MeshUniform, SetMeshBindGroup, SetMeshViewBindGroup},
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        mesh::MeshVertexBufferLayout,
+        render_asset::{PrepareAssetSet, RenderAssets},
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
+            RenderPhase, SetItemPipeline, TrackedRenderPass,
+        },
+        render_resource::{
+            AsBindGroup, AsBindGroupError, BindGroup, BindGroupLayout, CachedRenderPipelineId,
+            OwnedBindingResource, PipelineCache, RenderPipelineDescriptor, ShaderRef,
+            SpecializedMeshPipeline, SpecializedMeshPipelineError, SpecializedMeshPipelines,
+        },
+        renderer::RenderDevice,
+        texture::FallbackImage,
+        view::ExtractedView,
+        Extract, RenderApp, RenderSet,
+    },
+    utils::{HashMap, HashSet},
+};
+
+use crate::{
+    diagnostic::GrassRenderStats,
+    grass_spawner::GrassSpawner,
+    render::{
+        cache::GrassCache,
+        draw_mesh::DrawMeshInstanced,
+        grass_pipeline::{GrassPipeline, GrassRenderKey},
+    },
+};
+
+/// The index of the bind group of a [`GrassMaterial`] in the grass shader
+pub const GRASS_MATERIAL_BIND_GROUP: usize = 6;
+
+/// A material changing the look of the grass, similar to bevy's [`Material`](bevy::pbr::Material).
+///
+/// The grass is still positioned by the built-in vertex shader,
+/// but the fragment shader and its bindings are defined by the material.
+/// The bindings of the material are placed in bind group 6.
+///
+/// A fragment shader can import the output of the vertex shader using `#import warbler_grass::grass_types`:
+/// ```wgsl
+/// #import warbler_grass::grass_types
+///
+/// @group(6) @binding(0)
+/// var<uniform> tint: vec4<f32>;
+///
+/// @fragment
+/// fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+///     return in.color * tint;
+/// }
+/// ```
+///
+/// A material is used by adding a [`Handle`] to it to a grass chunk
+/// and adding the [`GrassMaterialPlugin`] of the material to the app.
+pub trait GrassMaterial: AsBindGroup + Send + Sync + Clone + TypeUuid + Sized + 'static {
+    /// Returns the fragment shader of this material.
+    /// If [`ShaderRef::Default`] is returned, the default grass fragment shader is used
+    fn fragment_shader() -> ShaderRef {
+        ShaderRef::Default
+    }
+
+    /// Customizes the [`RenderPipelineDescriptor`] of the grass using this material
+    #[allow(unused_variables)]
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayout,
+        key: GrassMaterialKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        Ok(())
+    }
+}
+
+/// Adds the render logic needed to draw grass using the [`GrassMaterial`] `M`.
+///
+/// The [`WarblersPlugin`](crate::warblers_plugin::WarblersPlugin) needs to be added before this plugin.
+pub struct GrassMaterialPlugin<M: GrassMaterial>(PhantomData<M>);
+
+impl<M: GrassMaterial> Default for GrassMaterialPlugin<M> {
+    fn default() -> Self {
+        GrassMaterialPlugin(PhantomData)
+    }
+}
+
+impl<M: GrassMaterial> Plugin for GrassMaterialPlugin<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    fn build(&self, app: &mut App) {
+        app.add_asset::<M>();
+        app.sub_app_mut(RenderApp)
+            .add_render_command::<Opaque3d, DrawGrassMaterial<M>>()
+            .init_resource::<GrassMaterialPipeline<M>>()
+            .init_resource::<ExtractedGrassMaterials<M>>()
+            .init_resource::<RenderGrassMaterials<M>>()
+            .init_resource::<SpecializedMeshPipelines<GrassMaterialPipeline<M>>>()
+            .add_systems(
+                (
+                    extract_grass_materials::<M>,
+                    extract_grass_material_handles::<M>,
+                )
+                    .in_schedule(ExtractSchedule),
+            )
+            .add_system(
+                prepare_grass_materials::<M>
+                    .in_set(RenderSet::Prepare)
+                    .after(PrepareAssetSet::PreAssetPrepare),
+            )
+            .add_system(queue_grass_materials::<M>.in_set(RenderSet::Queue));
+    }
+}
+
+/// A key uniquely identifying a specialized [`GrassMaterialPipeline`]
+pub struct GrassMaterialKey<M: GrassMaterial> {
+    pub(crate) grass_key: GrassRenderKey,
+    pub bind_group_data: M::Data,
+}
+
+impl<M: GrassMaterial> GrassMaterialKey<M> {
+    /// The key of the mesh pipeline the grass pipeline is based on
+    pub fn mesh_key(&self) -> MeshPipelineKey {
+        self.grass_key.mesh_key
+    }
+}
+
+impl<M: GrassMaterial> Eq for GrassMaterialKey<M> where M::Data: PartialEq {}
+
+impl<M: GrassMaterial> PartialEq for GrassMaterialKey<M>
+where
+    M::Data: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.grass_key == other.grass_key && self.bind_group_data == other.bind_group_data
+    }
+}
+
+impl<M: GrassMaterial> Clone for GrassMaterialKey<M>
+where
+    M::Data: Clone,
+{
+    fn clone(&self) -> Self {
+        GrassMaterialKey {
+            grass_key: self.grass_key.clone(),
+            bind_group_data: self.bind_group_data.clone(),
+        }
+    }
+}
+
+impl<M: GrassMaterial> Hash for GrassMaterialKey<M>
+where
+    M::Data: Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.grass_key.hash(state);
+        self.bind_group_data.hash(state);
+    }
+}
+
+/// The render pipeline of grass using the [`GrassMaterial`] `M`
+#[derive(Resource)]
+pub struct GrassMaterialPipeline<M: GrassMaterial> {
+    grass_pipeline: GrassPipeline,
+    pub material_layout: BindGroupLayout,
+    pub fragment_shader: Option<Handle<Shader>>,
+    marker: PhantomData<M>,
+}
+
+impl<M: GrassMaterial> FromWorld for GrassMaterialPipeline<M> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let render_device = world.resource::<RenderDevice>();
+        GrassMaterialPipeline {
+            grass_pipeline: world.resource::<GrassPipeline>().clone(),
+            material_layout: M::bind_group_layout(render_device),
+            fragment_shader: match M::fragment_shader() {
+                ShaderRef::Default => None,
+                ShaderRef::Handle(handle) => Some(handle),
+                ShaderRef::Path(path) => Some(asset_server.load(path)),
+            },
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M: GrassMaterial> SpecializedMeshPipeline for GrassMaterialPipeline<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    type Key = GrassMaterialKey<M>;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut descriptor = self
+            .grass_pipeline
+            .specialize(key.grass_key.clone(), layout)?;
+        descriptor.label = Some("Grass Material Render Pipeline".into());
+        descriptor.layout.push(self.material_layout.clone());
+        if let Some(fragment_shader) = &self.fragment_shader {
+            descriptor.fragment.as_mut().unwrap().shader = fragment_shader.clone();
+        }
+        M::specialize(&mut descriptor, layout, key)?;
+        Ok(descriptor)
+    }
+}
+
+/// Marks grass chunks in the render world which are drawn using a [`GrassMaterial`]
+#[derive(Component)]
+pub(crate) struct GrassMaterialInstance;
+
+type DrawGrassMaterial<M> = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetMeshBindGroup<1>,
+    SetGrassMaterialBindGroup<M, GRASS_MATERIAL_BIND_GROUP>,
+    DrawMeshInstanced,
+);
+
+/// Sets the bind group of a [`GrassMaterial`] at the index `I`
+pub struct SetGrassMaterialBindGroup<M: GrassMaterial, const I: usize>(PhantomData<M>);
+impl<P: PhaseItem, M: GrassMaterial, const I: usize> RenderCommand<P>
+    for SetGrassMaterialBindGroup<M, I>
+{
+    type Param = SRes<RenderGrassMaterials<M>>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<Handle<M>>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        material_handle: &'_ Handle<M>,
+        materials: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(material) = materials.into_inner().get(material_handle) else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, &material.bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+/// The data of a [`GrassMaterial`] prepared for the gpu
+pub struct PreparedGrassMaterial<M: GrassMaterial> {
+    pub bindings: Vec<OwnedBindingResource>,
+    pub bind_group: BindGroup,
+    pub key: M::Data,
+}
+
+/// The created or modified [`GrassMaterial`]s of a frame
+#[derive(Resource)]
+struct ExtractedGrassMaterials<M: GrassMaterial> {
+    extracted: Vec<(Handle<M>, M)>,
+    removed: Vec<Handle<M>>,
+}
+
+impl<M: GrassMaterial> Default for ExtractedGrassMaterials<M> {
+    fn default() -> Self {
+        ExtractedGrassMaterials {
+            extracted: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+}
+
+/// Stores all prepared [`GrassMaterial`]s of the type `M`
+#[derive(Resource, Deref, DerefMut)]
+pub struct RenderGrassMaterials<M: GrassMaterial>(HashMap<Handle<M>, PreparedGrassMaterial<M>>);
+
+impl<M: GrassMaterial> Default for RenderGrassMaterials<M> {
+    fn default() -> Self {
+        RenderGrassMaterials(HashMap::default())
+    }
+}
+
+/// Extracts all created or modified materials into the render world
+fn extract_grass_materials<M: GrassMaterial>(
+    mut commands: Commands,
+    mut events: Extract<EventReader<AssetEvent<M>>>,
+    assets: Extract<Res<Assets<M>>>,
+) {
+    let mut changed = HashSet::new();
+    let mut removed = Vec::new();
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                changed.insert(handle.clone_weak());
+            }
+            AssetEvent::Removed { handle } => {
+                changed.remove(handle);
+                removed.push(handle.clone_weak());
+            }
+        }
+    }
+    let extracted = changed
+        .into_iter()
+        .filter_map(|handle| {
+            let material = assets.get(&handle)?.clone();
+            Some((handle, material))
+        })
+        .collect();
+    commands.insert_resource(ExtractedGrassMaterials { extracted, removed });
+}
+
+/// Extracts the material handles of all visible grass chunks
+#[allow(clippy::type_complexity)]
+fn extract_grass_material_handles<M: GrassMaterial>(
+    mut commands: Commands,
+    chunks: Extract<Query<(Entity, &ComputedVisibility, &Handle<M>), With<GrassSpawner>>>,
+    mut previous_len: Local<usize>,
+) {
+    let mut values = Vec::with_capacity(*previous_len);
+    for (entity, visibility, handle) in chunks.iter() {
+        if visibility.is_visible() {
+            values.push((entity, (handle.clone_weak(), GrassMaterialInstance)));
+        }
+    }
+    *previous_len = values.len();
+    commands.insert_or_spawn_batch(values);
+}
+
+/// Prepares the extracted materials.
+///
+/// Materials whose textures aren't loaded yet are prepared again in the next frame
+fn prepare_grass_materials<M: GrassMaterial>(
+    mut prepare_next_frame: Local<Vec<(Handle<M>, M)>>,
+    mut extracted: ResMut<ExtractedGrassMaterials<M>>,
+    mut render_materials: ResMut<RenderGrassMaterials<M>>,
+    render_device: Res<RenderDevice>,
+    images: Res<RenderAssets<Image>>,
+    fallback_image: Res<FallbackImage>,
+    pipeline: Res<GrassMaterialPipeline<M>>,
+) {
+    for removed in std::mem::take(&mut extracted.removed) {
+        render_materials.remove(&removed);
+    }
+    let queued = std::mem::take(&mut *prepare_next_frame);
+    for (handle, material) in queued
+        .into_iter()
+        .chain(std::mem::take(&mut extracted.extracted))
+    {
+        match material.as_bind_group(
+            &pipeline.material_layout,
+            &render_device,
+            &images,
+            &fallback_image,
+        ) {
+            Ok(prepared) => {
+                render_materials.insert(
+                    handle,
+                    PreparedGrassMaterial {
+                        bindings: prepared.bindings,
+                        bind_group: prepared.bind_group,
+                        key: prepared.data,
+                    },
+                );
+            }
+            Err(AsBindGroupError::RetryNextUpdate) => {
+                prepare_next_frame.push((handle, material));
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn queue_grass_materials<M: GrassMaterial>(
+    opaque_3d_draw_functions: Res<DrawFunctions<Opaque3d>>,
+    material_pipeline: Res<GrassMaterialPipeline<M>>,
+    msaa: Res<Msaa>,
+    mut pipelines: ResMut<SpecializedMeshPipelines<GrassMaterialPipeline<M>>>,
+    pipeline_cache: Res<PipelineCache>,
+    grass_cache: Res<GrassCache>,
+    meshes: Res<RenderAssets<Mesh>>,
+    render_materials: Res<RenderGrassMaterials<M>>,
+    material_meshes: Query<(Entity, &MeshUniform, &Handle<Mesh>, &Handle<M>)>,
+    mut views: Query<(&ExtractedView, &mut RenderPhase<Opaque3d>)>,
+    stats: Res<GrassRenderStats>,
+    mut specialized_pipelines: Local<HashSet<CachedRenderPipelineId>>,
+) where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    let draw_function = opaque_3d_draw_functions
+        .read()
+        .get_id::<DrawGrassMaterial<M>>()
+        .unwrap();
+    let msaa_key = MeshPipelineKey::from_msaa_samples(msaa.samples());
+
+    for (view, mut opaque_phase) in &mut views {
+        let view_key = msaa_key | MeshPipelineKey::from_hdr(view.hdr);
+        let rangefinder = view.rangefinder3d();
+        for (entity, mesh_uniform, mesh_handle, material_handle) in &material_meshes {
+            // empty chunks aren't drawn
+            let Some(chunk) = grass_cache
+                .get(&entity)
+                .filter(|chunk| chunk.instance_count > 0)
+            else {
+                continue;
+            };
+            let (Some(mesh), Some(material)) = (
+                meshes.get(mesh_handle),
+                render_materials.get(material_handle),
+            ) else {
+                continue;
+            };
+            let mesh_key =
+                view_key | MeshPipelineKey::from_primitive_topology(mesh.primitive_topology);
+            let key = GrassMaterialKey {
+                grass_key: GrassRenderKey::from(mesh_key).with_flags(chunk.flags),
+                bind_group_data: material.key.clone(),
+            };
+            let pipeline = match pipelines.specialize(
+                &pipeline_cache,
+                &material_pipeline,
+                key,
+                &mesh.layout,
+            ) {
+                Ok(pipeline) => pipeline,
+                Err(err) => {
+                    error!("{}", err);
+                    continue;
+                }
+            };
+            if specialized_pipelines.insert(pipeline) {
+                stats.specializations.fetch_add(1, Ordering::Relaxed);
+            }
+            opaque_phase.add(Opaque3d {
+                entity,
+                pipeline,
+                draw_function,
+                distance: rangefinder.distance(&mesh_uniform.transform),
+            });
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+
+/// The kind of noise summed up by a [`FractalNoise`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, FromReflect)]
+pub enum NoiseKind {
+    /// Interpolates between random values. Looks blocky at low octave counts
+    Value,
+    /// Interpolates between random gradients. Looks smoother than value noise
+    #[default]
+    Perlin,
+    /// Sums up gradients of the corners of a triangle grid. Has less directional artifacts than perlin noise
+    Simplex,
+}
+
+/// Deterministic fractal noise on the x,z plane, calculated on the CPU.
+///
+/// Sums up multiple layers (octaves) of noise with increasing frequency and decreasing amplitude.
+/// The noise only depends on the sampled position and the seed,
+/// so chunks sampling the noise at their world positions fit together without seams.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, FromReflect)]
+pub struct FractalNoise {
+    pub kind: NoiseKind,
+    pub seed: u32,
+    /// The frequency of the first octave. Higher frequencies result in smaller features
+    pub frequency: f32,
+    /// The number of layers of noise
+    pub octaves: u32,
+    /// The factor by which the frequency increases in each octave
+    pub lacunarity: f32,
+    /// The factor by which the amplitude decreases in each octave
+    pub persistence: f32,
+}
+
+impl Default for FractalNoise {
+    fn default() -> Self {
+        FractalNoise {
+            kind: NoiseKind::default(),
+            seed: 0,
+            frequency: 0.05,
+            octaves: 4,
+            lacunarity: 2.,
+            persistence: 0.5,
+        }
+    }
+}
+
+impl FractalNoise {
+    pub fn new(seed: u32) -> Self {
+        FractalNoise { seed, ..default() }
+    }
+    pub fn with_kind(mut self, kind: NoiseKind) -> Self {
+        self.kind = kind;
+        self
+    }
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        assert!(octaves > 0);
+        self.octaves = octaves;
+        self
+    }
+    pub fn with_lacunarity(mut self, lacunarity: f32) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+    pub fn with_persistence(mut self, persistence: f32) -> Self {
+        self.persistence = persistence;
+        self
+    }
+    /// Returns the value of the noise at the position, in the range `[0, 1]`
+    pub fn sample(&self, position: Vec2) -> f32 {
+        let mut frequency = self.frequency;
+        let mut amplitude = 1.;
+        let mut sum = 0.;
+        let mut amplitude_sum = 0.;
+        for octave in 0..self.octaves {
+            let seed = self.seed.wrapping_add(octave.wrapping_mul(0x9E37_79B9));
+            let value = match self.kind {
+                NoiseKind::Value => value_noise(position * frequency, seed),
+                NoiseKind::Perlin => perlin_noise(position * frequency, seed),
+                NoiseKind::Simplex => simplex_noise(position * frequency, seed),
+            };
+            sum += value * amplitude;
+            amplitude_sum += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.persistence;
+        }
+        (sum / amplitude_sum).clamp(0., 1.)
+    }
+}
+
+/// Hashes a lattice point to a pseudo random number
+fn hash(x: i32, y: i32, seed: u32) -> u32 {
+    let mut h = seed ^ (x as u32).wrapping_mul(0x27d4_eb2d) ^ (y as u32).wrapping_mul(0x1656_67b1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^ (h >> 16)
+}
+
+/// A random unit vector for a lattice point
+fn gradient(x: i32, y: i32, seed: u32) -> Vec2 {
+    let angle = hash(x, y, seed) as f32 / u32::MAX as f32 * std::f32::consts::TAU;
+    Vec2::new(angle.cos(), angle.sin())
+}
+
+/// The quintic fade curve used by improved perlin noise
+fn fade(t: Vec2) -> Vec2 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Value noise in the range `[0, 1]`
+fn value_noise(position: Vec2, seed: u32) -> f32 {
+    let cell = position.floor();
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let t = fade(position - cell);
+    let value = |x, y| hash(x, y, seed) as f32 / u32::MAX as f32;
+    lerp(
+        lerp(value(x, y), value(x + 1, y), t.x),
+        lerp(value(x, y + 1), value(x + 1, y + 1), t.x),
+        t.y,
+    )
+}
+
+/// Perlin noise mapped to the range `[0, 1]`
+fn perlin_noise(position: Vec2, seed: u32) -> f32 {
+    let cell = position.floor();
+    let (x, y) = (cell.x as i32, cell.y as i32);
+    let offset = position - cell;
+    let t = fade(offset);
+    let gradient = |x, y, offset: Vec2| gradient(x, y, seed).dot(offset);
+    let value = lerp(
+        lerp(
+            gradient(x, y, offset),
+            gradient(x + 1, y, offset - Vec2::X),
+            t.x,
+        ),
+        lerp(
+            gradient(x, y + 1, offset - Vec2::Y),
+            gradient(x + 1, y + 1, offset - Vec2::ONE),
+            t.x,
+        ),
+        t.y,
+    );
+    // perlin noise with unit gradients is in the range [-sqrt(0.5), sqrt(0.5)]
+    value * std::f32::consts::FRAC_1_SQRT_2 + 0.5
+}
+
+/// 2D simplex noise mapped to the range `[0, 1]`
+fn simplex_noise(position: Vec2, seed: u32) -> f32 {
+    // factors to skew the triangle grid to a square grid and back
+    let skew = (3f32.sqrt() - 1.) / 2.;
+    let unskew = (3. - 3f32.sqrt()) / 6.;
+    let cell = (position + (position.x + position.y) * skew).floor();
+    let origin = cell - (cell.x + cell.y) * unskew;
+    let offset = position - origin;
+    // the triangle containing the position is decided by which side of the diagonal it lies on
+    let middle = if offset.x > offset.y {
+        IVec2::X
+    } else {
+        IVec2::Y
+    };
+    let cell = cell.as_ivec2();
+    let corners = [
+        (IVec2::ZERO, offset),
+        (middle, offset - middle.as_vec2() + unskew),
+        (IVec2::ONE, offset - 1. + 2. * unskew),
+    ];
+    let value: f32 = corners
+        .iter()
+        .map(|(corner, offset)| {
+            let falloff = 0.5 - offset.length_squared();
+            if falloff <= 0. {
+                return 0.;
+            }
+            let corner = cell + *corner;
+            falloff.powi(4) * gradient(corner.x, corner.y, seed).dot(*offset)
+        })
+        .sum();
+    // scales the value roughly to the range [-1, 1]
+    (value * 70. * 0.5 + 0.5).clamp(0., 1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KINDS: [NoiseKind; 3] = [NoiseKind::Value, NoiseKind::Perlin, NoiseKind::Simplex];
+
+    #[test]
+    fn noise_stays_in_range() {
+        for kind in KINDS {
+            let noise = FractalNoise::new(3).with_kind(kind).with_frequency(0.37);
+            for x in -50..50 {
+                for y in -50..50 {
+                    let value = noise.sample(Vec2::new(x as f32, y as f32) * 0.9);
+                    assert!((0. ..=1.).contains(&value), "{kind:?} noise is {value}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn noise_is_continuous_across_lattice_lines() {
+        let epsilon = 1e-4;
+        for kind in KINDS {
+            let noise = FractalNoise::new(11).with_kind(kind).with_frequency(1.);
+            for line in -3..=3 {
+                for i in 0..10 {
+                    let along = i as f32 * 0.37 - 2.;
+                    let line = line as f32;
+                    let pairs = [
+                        (
+                            Vec2::new(line - epsilon, along),
+                            Vec2::new(line + epsilon, along),
+                        ),
+                        (
+                            Vec2::new(along, line - epsilon),
+                            Vec2::new(along, line + epsilon),
+                        ),
+                    ];
+                    for (a, b) in pairs {
+                        let difference = (noise.sample(a) - noise.sample(b)).abs();
+                        assert!(difference < 1e-2, "{kind:?} noise jumps at {a}");
+                    }
+                }
+            }
+        }
+    }
+}
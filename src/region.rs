@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+/// An area on the x,z plane, used to select grass blades.
+///
+/// The coordinates are in the same space as the positions of the grass blades.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GrassRegion {
+    /// All points which are at most `radius` away from `center`
+    Circle { center: Vec2, radius: f32 },
+    /// All points between `min` and `max`
+    Rect { min: Vec2, max: Vec2 },
+    /// All points inside the polygon defined by its corners.
+    ///
+    /// The polygon is closed automatically, so the last corner should not be a copy of the first.
+    Polygon(Vec<Vec2>),
+}
+
+impl GrassRegion {
+    /// Returns true if the point lies inside of the region
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self {
+            GrassRegion::Circle { center, radius } => {
+                point.distance_squared(*center) <= radius * radius
+            }
+            GrassRegion::Rect { min, max } => point.cmpge(*min).all() && point.cmple(*max).all(),
+            GrassRegion::Polygon(corners) => polygon_contains(corners, point),
+        }
+    }
+    /// Returns the distance of the point to the region, which is zero for points inside of the region
+    pub fn distance(&self, point: Vec2) -> f32 {
+        match self {
+            GrassRegion::Circle { center, radius } => (point.distance(*center) - radius).max(0.),
+            GrassRegion::Rect { min, max } => {
+                (*min - point).max(point - *max).max(Vec2::ZERO).length()
+            }
+            GrassRegion::Polygon(corners) => {
+                if polygon_contains(corners, point) {
+                    return 0.;
+                }
+                let mut previous = match corners.last() {
+                    Some(corner) => *corner,
+                    None => return f32::MAX,
+                };
+                let mut distance = f32::MAX;
+                for &corner in corners {
+                    distance = distance.min(segment_distance(previous, corner, point));
+                    previous = corner;
+                }
+                distance
+            }
+        }
+    }
+    /// Returns the minimal and maximal corner of the smallest rectangle containing the region
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        match self {
+            GrassRegion::Circle { center, radius } => (*center - *radius, *center + *radius),
+            GrassRegion::Rect { min, max } => (*min, *max),
+            GrassRegion::Polygon(corners) => corners.iter().fold(
+                (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+                |(min, max), corner| (min.min(*corner), max.max(*corner)),
+            ),
+        }
+    }
+}
+
+/// Checks if the point lies inside the polygon using the even-odd rule
+fn polygon_contains(corners: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    let mut previous = match corners.last() {
+        Some(corner) => *corner,
+        None => return false,
+    };
+    for &corner in corners {
+        if (corner.y > point.y) != (previous.y > point.y) {
+            let intersection_x =
+                (previous.x - corner.x) * (point.y - corner.y) / (previous.y - corner.y) + corner.x;
+            if point.x < intersection_x {
+                inside = !inside;
+            }
+        }
+        previous = corner;
+    }
+    inside
+}
+
+/// Returns the distance of the point to the line segment from `a` to `b`
+fn segment_distance(a: Vec2, b: Vec2, point: Vec2) -> f32 {
+    let segment = b - a;
+    let length_squared = segment.length_squared();
+    if length_squared == 0. {
+        return point.distance(a);
+    }
+    let t = ((point - a).dot(segment) / length_squared).clamp(0., 1.);
+    point.distance(a + segment * t)
+}
@@ -4,7 +4,7 @@ use bevy::{
 };
 
 pub(crate) mod cache;
-mod draw_mesh;
+pub(crate) mod draw_mesh;
 pub(crate) mod extract;
 pub(crate) mod grass_pipeline;
 pub(crate) mod prepare;
@@ -1,11 +1,21 @@
 #import bevy_pbr::mesh_types
 #import bevy_pbr::mesh_view_bindings
+#import warbler_grass::grass_types
+#import warbler_grass::wind
 
 struct ShaderRegionConfiguration {
     main_color: vec4<f32>,
     bottom_color: vec4<f32>,
     wind: vec2<f32>,
-    _wasm_padding: vec2<f32>,
+    trail_effect: vec2<f32>,
+    trail_area: vec4<f32>,
+    state_area: vec4<f32>,
+    burnt_color: vec4<f32>,
+    frost_color: vec4<f32>,
+    // burnt shrink (x), wet darken (y) and wet gloss (z)
+    state_effect: vec4<f32>,
+    // how far the wind moved over the noise texture
+    noise_offset: vec2<f32>,
 };
 @group(1) @binding(0)
 var<uniform> mesh: Mesh;
@@ -16,12 +26,32 @@ var<uniform> config: ShaderRegionConfiguration;
 @group(2) @binding(1)
 var noise_texture: texture_2d<f32>;
 
+@group(2) @binding(2)
+var trail_map: texture_2d<f32>;
+
+@group(2) @binding(3)
+var state_map: texture_2d<f32>;
+
+@group(2) @binding(4)
+var coverage_mask: texture_2d<f32>;
+
 #ifdef HEIGHT_MAP
+    struct HeightMapConfiguration {
+        origin: vec2<f32>,
+        size: vec2<f32>,
+        // selects the channel containing the heights
+        channel_mask: vec4<f32>,
+        height: f32,
+        base_offset: f32,
+        // 0 for nearest and 1 for bilinear filtering
+        filter_mode: u32,
+        _wasm_padding: f32,
+    };
     @group(3) @binding(0)
     var height_map: texture_2d<f32>;
 
     @group(3) @binding(1)
-    var<uniform> aabb: vec3<f32>;
+    var<uniform> height_map_config: HeightMapConfiguration;
 #else
     @group(3) @binding(0)
     var y_positions: texture_2d<f32>;
@@ -34,37 +64,140 @@ var xz_positions: texture_2d<f32>;
 @group(5) @binding(0)
 var heights: texture_2d<f32>;
 
-#import bevy_pbr::mesh_functions
+@group(5) @binding(1)
+var colors: texture_2d<f32>;
 
-struct VertexOutput {
-    @builtin(position) clip_position: vec4<f32>,
-    @location(0) color: vec4<f32>,
+struct GrowthConfiguration {
+    elapsed: f32,
+    duration: f32,
+    // the index of the easing curve
+    easing: u32,
+    _wasm_padding: f32,
 };
+@group(5) @binding(2)
+var<uniform> growth: GrowthConfiguration;
 
-const NOISE_TEXTURE_SPEED: f32 = 30.;
-const NOISE_TEXTURE_ZOOM: f32 = 5.;
+@group(5) @binding(3)
+var growth_delays: texture_2d<f32>;
 
-fn wind_offset(vertex_position: vec2<f32>) -> vec2<f32> {
-    var texture_offset = config.wind.xy * globals.time * NOISE_TEXTURE_SPEED;
-    var texture_position = vec2<f32>(vertex_position.x ,vertex_position.y) * NOISE_TEXTURE_ZOOM + texture_offset;
-    
-    // dimensions of noise texture in vec2<u32>
-    let dim = textureDimensions(noise_texture, 0);
+struct CoverageConfiguration {
+    color: vec4<f32>,
+    amount: f32,
+    height_threshold: f32,
+    _wasm_padding: vec2<f32>,
+    // the origin (xy) and size (zw) of the coverage mask
+    mask_area: vec4<f32>,
+};
+@group(5) @binding(4)
+var<uniform> coverage: CoverageConfiguration;
 
-    // read just position in case of a over/under flow of tex. coords
-    texture_position = abs(texture_position % vec2<f32>(dim));
-    var texture_pixel = textureLoad(noise_texture, vec2<i32>(i32(texture_position.x),i32(texture_position.y)), 0);
-    return texture_pixel.xy * config.wind;
+#import bevy_pbr::mesh_functions
+
+// returns how far the blade has grown, from 0 to 1
+fn growth_progress(instance_index: u32) -> f32 {
+    var elapsed = growth.elapsed;
+    #ifdef PER_BLADE_GROWTH
+        elapsed -= storage_pixel_from_texture(instance_index, growth_delays).r;
+    #endif
+    if growth.duration <= 0. {
+        return select(0., 1., elapsed >= 0.);
+    }
+    return ease(elapsed / growth.duration, growth.easing);
+}
+// returns the amount of trampling (x) and the direction the blade is pressed to (yz)
+fn trail_at(world_position: vec2<f32>) -> vec3<f32> {
+    // a trail map with no size is not used
+    if config.trail_area.z <= 0. || config.trail_area.w <= 0. {
+        return vec3<f32>(0.);
+    }
+    let uv = (world_position - config.trail_area.xy) / config.trail_area.zw;
+    if any(uv < vec2<f32>(0.)) || any(uv >= vec2<f32>(1.)) {
+        return vec3<f32>(0.);
+    }
+    let dim = textureDimensions(trail_map, 0);
+    let texture_position = vec2<i32>(uv * vec2<f32>(dim));
+    let texel = textureLoad(trail_map, texture_position, 0);
+    return vec3<f32>(texel.r, texel.gb * 2. - 1.);
+}
+// returns how burnt (x), frozen (y) and wet (z) the grass is
+fn state_at(world_position: vec2<f32>) -> vec3<f32> {
+    // a state map with no size is not used
+    if config.state_area.z <= 0. || config.state_area.w <= 0. {
+        return vec3<f32>(0.);
+    }
+    let uv = (world_position - config.state_area.xy) / config.state_area.zw;
+    if any(uv < vec2<f32>(0.)) || any(uv >= vec2<f32>(1.)) {
+        return vec3<f32>(0.);
+    }
+    let dim = textureDimensions(state_map, 0);
+    let texture_position = vec2<i32>(uv * vec2<f32>(dim));
+    return textureLoad(state_map, texture_position, 0).rgb;
+}
+// returns how much the grass at the position is covered, from 0 to 1
+fn coverage_at(world_position: vec2<f32>) -> f32 {
+    // a mask with no size doesn't limit the coverage
+    if coverage.mask_area.z <= 0. || coverage.mask_area.w <= 0. {
+        return coverage.amount;
+    }
+    let uv = (world_position - coverage.mask_area.xy) / coverage.mask_area.zw;
+    if any(uv < vec2<f32>(0.)) || any(uv >= vec2<f32>(1.)) {
+        return coverage.amount;
+    }
+    let dim = textureDimensions(coverage_mask, 0);
+    let texture_position = vec2<i32>(uv * vec2<f32>(dim));
+    return coverage.amount * textureLoad(coverage_mask, texture_position, 0).r;
 }
 #ifdef HEIGHT_MAP
+    // loads a texel of the height map, texels outside of the texture are clamped to the edge
+    fn height_map_texel(texel_position: vec2<i32>) -> f32 {
+        let dim = vec2<i32>(textureDimensions(height_map, 0));
+        let clamped_position = clamp(texel_position, vec2<i32>(0), dim - vec2<i32>(1));
+        return dot(textureLoad(height_map, clamped_position, 0), height_map_config.channel_mask);
+    }
     fn height_map_offset(vertex_position: vec2<f32>) -> f32 {
-        let dim = textureDimensions(height_map, 0);
-        let texture_position = abs((vertex_position.xy / aabb.xz ) * vec2<f32>(dim)) ;
-        var texture_r = textureLoad(height_map, vec2<i32>(i32(texture_position.x),i32(texture_position.y)), 0).r;
-        return texture_r * aabb.y;
+        let dim = vec2<f32>(textureDimensions(height_map, 0));
+        let uv = clamp((vertex_position - height_map_config.origin) / height_map_config.size, vec2<f32>(0.), vec2<f32>(1.));
+        var value: f32;
+        if height_map_config.filter_mode == 1u {
+            // texel values are defined at the center of the texels
+            let texel_position = uv * dim - vec2<f32>(0.5);
+            let base = floor(texel_position);
+            let t = texel_position - base;
+            let p = vec2<i32>(base);
+            let bottom = mix(height_map_texel(p), height_map_texel(p + vec2<i32>(1, 0)), t.x);
+            let top = mix(height_map_texel(p + vec2<i32>(0, 1)), height_map_texel(p + vec2<i32>(1, 1)), t.x);
+            value = mix(bottom, top, t.y);
+        } else {
+            value = height_map_texel(vec2<i32>(uv * dim));
+        }
+        return height_map_config.base_offset + value * height_map_config.height;
     }
 #endif
 
+// maps the linear progress of the growth to the eased progress, like `GrowthEasing::ease`
+fn ease(t: f32, easing: u32) -> f32 {
+    let t = clamp(t, 0., 1.);
+    switch easing {
+        case 1u: {
+            return t * t;
+        }
+        case 2u: {
+            return 1. - (1. - t) * (1. - t);
+        }
+        case 3u: {
+            return t * t * (3. - 2. * t);
+        }
+        case 4u: {
+            let overshoot = 1.70158;
+            let s = t - 1.;
+            return 1. + s * s * (overshoot + 1.) * s + s * s * overshoot;
+        }
+        default: {
+            return t;
+        }
+    }
+}
+
 // 2d textures are used to store vertex information.
 // normally this would be done using storage buffers.
 // Storage buffer as of now are not supported by wgsl, therefore this hack is used
@@ -91,23 +224,62 @@ fn vertex(@location(0) vertex_position: vec3<f32>, @builtin(instance_index) inst
         // from explicit y positions
         position_field_offset.y = storage_pixel_from_texture(instance_index, y_positions).r;
     #endif
+    // the root of the blade in world space, where the wind, trail, state and coverage are read
+    let world_base = mesh_position_local_to_world(mesh.model, vec4<f32>(position_field_offset, 1.0));
+    let state = state_at(world_base.xz);
     // ---HEIGHT---
-    let height = storage_pixel_from_texture(instance_index, heights).r;
-    var position = vertex_position * vec3<f32>(1.,height, 1.) + position_field_offset;
+    // burnt blades are shortened
+    let height = storage_pixel_from_texture(instance_index, heights).r * (1. - state.x * config.state_effect.x);
+    // ---GROWTH---
+    // growing blades are scaled as a whole, so they don't look stretched
+    let grown = growth_progress(instance_index);
+    let position = vertex_position * vec3<f32>(grown, height * grown, grown) + position_field_offset;
+
+    var world_position = mesh_position_local_to_world(mesh.model, vec4<f32>(position, 1.0));
 
     // ---WIND---
+    // the wind is sampled and applied in world space, so moving and rotating chunks stay in the same wind.
     // only applies wind if the vertex is not on the bottom of the grass (or very small)
-    let offset = wind_offset(position_field_offset.xz);
-    let strength = max(0.,log(vertex_position.y + 1.));
-    position.x += offset.x * strength;
-    position.z += offset.y * strength;
+    let offset = wind_offset(noise_texture, config.wind, config.noise_offset, world_base.xz);
+    let strength = wind_strength(vertex_position.y) * grown;
+    world_position.x += offset.x * strength;
+    world_position.z += offset.y * strength;
+
+    // ---TRAIL---
+    // trampled blades are pressed to the ground in world space
+    let trail = trail_at(world_base.xz);
+    let trampling = trail.x * config.trail_effect.x;
+    let blade_offset = world_position.xyz - world_base.xyz;
+    world_position.y -= blade_offset.y * trampling;
+    world_position.x += trail.y * blade_offset.y * trampling;
+    world_position.z += trail.z * blade_offset.y * trampling;
 
     // ---CLIP_POSITION---
-    out.clip_position = mesh_position_local_to_clip(mesh.model, vec4<f32>(position, 1.0));
+    out.clip_position = mesh_position_world_to_clip(world_position);
+    out.world_position = world_position;
+    out.blade_height = vertex_position.y;
 
     // ---COLOR---
     let lambda = clamp(vertex_position.y, 0.,1.);
     out.color = mix(config.bottom_color, config.main_color, lambda);
+    #ifdef PER_BLADE_COLOR
+        out.color *= storage_pixel_from_texture(instance_index, colors);
+    #endif
+    out.color = vec4<f32>(out.color.rgb * (1. - trail.x * config.trail_effect.y), out.color.a);
+
+    // ---STATE---
+    // burnt blades are charred, frozen blades get white tips and wet blades get darker with glossy tips
+    out.color = vec4<f32>(mix(out.color.rgb, config.burnt_color.rgb, state.x), out.color.a);
+    out.color = vec4<f32>(mix(out.color.rgb, config.frost_color.rgb, state.y * lambda * lambda), out.color.a);
+    let wet_color = out.color.rgb * (1. - state.z * config.state_effect.y);
+    let gloss = state.z * config.state_effect.z * pow(lambda, 4.);
+    out.color = vec4<f32>(wet_color + vec3<f32>(gloss), out.color.a);
+
+    // ---COVERAGE---
+    // the blade is covered above the threshold, with a short blend to the uncovered part
+    let covered = smoothstep(coverage.height_threshold - 0.1, coverage.height_threshold + 0.1, lambda);
+    let coverage_amount = clamp(coverage_at(world_base.xz), 0., 1.) * covered;
+    out.color = vec4<f32>(mix(out.color.rgb, coverage.color.rgb, coverage_amount), out.color.a);
     return out;
 }
 
@@ -0,0 +1,11 @@
+#define_import_path warbler_grass::grass_types
+
+// the output of the grass vertex shader, which is the input of the fragment shader of a grass material
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // the color of the blade, including the colors of the configuration, per blade colors and trampling
+    @location(0) color: vec4<f32>,
+    @location(1) world_position: vec4<f32>,
+    // the height of the vertex on the blade mesh, from 0 at the root to 1 at the tip
+    @location(2) blade_height: f32,
+};
@@ -0,0 +1,41 @@
+#define_import_path warbler_grass::wind
+
+// the wind parameters of the grass configuration
+struct GrassWind {
+    // the direction and strength of the wind on the x,z plane
+    wind: vec2<f32>,
+    // how far the wind moved over the noise texture, wrapped into the size of the texture
+    noise_offset: vec2<f32>,
+    // the elapsed time of the grass clock, wrapped around after an hour
+    time: f32,
+};
+
+// reads the wind of the grass from the wind texture set by the GrassWindPlugin
+fn load_grass_wind(wind_texture: texture_2d<f32>) -> GrassWind {
+    let wind = textureLoad(wind_texture, vec2<i32>(0, 0), 0);
+    let time = textureLoad(wind_texture, vec2<i32>(1, 0), 0).r;
+    return GrassWind(wind.xy, wind.zw, time);
+}
+
+const WIND_NOISE_ZOOM: f32 = 5.;
+
+// returns the offset on the x,z plane caused by the wind at the position.
+// the noise offset is calculated on the CPU, see `wind_noise_offset`
+fn wind_offset(noise_texture: texture_2d<f32>, wind: vec2<f32>, noise_offset: vec2<f32>, position: vec2<f32>) -> vec2<f32> {
+    let texture_position = position * WIND_NOISE_ZOOM + noise_offset;
+
+    // dimensions of noise texture in vec2<u32>
+    let dim = vec2<f32>(textureDimensions(noise_texture, 0));
+
+    // the noise texture tiles, positions outside of it are wrapped into it
+    let wrapped = texture_position - floor(texture_position / dim) * dim;
+    let texel = min(vec2<i32>(wrapped), vec2<i32>(dim) - vec2<i32>(1));
+    let texture_pixel = textureLoad(noise_texture, texel, 0);
+    return texture_pixel.xy * wind;
+}
+
+// returns how much a vertex at the height is moved by the wind.
+// the bottom of a blade (height 0) doesn't move at all
+fn wind_strength(height: f32) -> f32 {
+    return max(0., log(height + 1.));
+}
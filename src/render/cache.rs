@@ -1,9 +1,10 @@
 use bevy::{
     prelude::*,
-    render::render_resource::BindGroup,
+    render::render_resource::{BindGroup, Buffer, Texture, TextureViewId},
     utils::{HashMap, HashSet},
 };
 
+use super::prepare::{ShaderCoverageConfiguration, ShaderGrowthConfiguration};
 use crate::grass_spawner::GrassSpawnerFlags;
 
 #[derive(Resource, DerefMut, Deref, Debug, Default)]
@@ -18,11 +19,36 @@ pub struct CachedGrassChunk {
     pub height_map: Option<BindGroup>,
     pub explicit_y_buffer: Option<BindGroup>,
     pub height_buffer: Option<BindGroup>,
-    pub transform: GlobalTransform,
+    /// The texture containing the heights of the blades.
+    ///
+    /// Kept to update the heights without creating a new texture
+    pub height_texture: Option<Texture>,
+    /// The growth of the chunk, fully grown if the chunk has no [`GrassGrowth`](crate::growth::GrassGrowth)
+    pub growth: ShaderGrowthConfiguration,
+    /// The buffer containing the growth, written if the growth changed
+    pub growth_buffer: Option<Buffer>,
+    pub growth_changed: bool,
+    /// The coverage of the chunk, either its own [`GrassCoverage`](crate::coverage::GrassCoverage) or the global one
+    pub coverage: ShaderCoverageConfiguration,
+    /// The buffer containing the coverage, written if the coverage changed
+    pub coverage_buffer: Option<Buffer>,
+    pub coverage_changed: bool,
+    /// The texture of the own [`GrassCoverageMask`](crate::coverage::GrassCoverageMask) of the chunk
+    pub coverage_mask: Option<Handle<Image>>,
+    /// The view of the coverage mask in the uniform bind group, if the chunk uses its own mask
+    pub coverage_mask_view: Option<TextureViewId>,
     pub instance_count: usize,
+    /// The bytes of all textures and buffers created for the chunk
+    pub gpu_memory: u64,
 
     pub flags: GrassSpawnerFlags,
 }
+impl CachedGrassChunk {
+    /// Records the memory of a new texture or buffer of the chunk
+    pub fn add_gpu_memory(&mut self, bytes: u64) {
+        self.gpu_memory += bytes;
+    }
+}
 #[derive(Resource, DerefMut, Deref, Debug, Default)]
 pub struct EntityCache {
     pub entities: HashSet<Entity>,
@@ -38,6 +38,10 @@ impl<P: PhaseItem> RenderCommand<P> for DrawMeshInstanced {
             return RenderCommandResult::Failure;
         }
         let chunk = &cache.into_inner()[&entity];
+        // empty chunks don't draw anything
+        if chunk.instance_count == 0 {
+            return RenderCommandResult::Success;
+        }
         // set uniforms
         pass.set_bind_group(2, chunk.uniform_bindgroup.as_ref().unwrap(), &[]);
         if chunk.flags.contains(GrassSpawnerFlags::HEIGHT_MAP) {
@@ -1,5 +1,11 @@
 use super::cache::{EntityCache, GrassCache};
-use crate::grass_spawner::GrassSpawner;
+use super::prepare::{ShaderCoverageConfiguration, ShaderGrowthConfiguration};
+use crate::coverage::{GrassCoverage, GrassCoverageMask};
+use crate::grass_spawner::{GrassSpawner, GrassUpdate, HeightRepresentation};
+use crate::growth::GrassGrowth;
+use crate::GrassConfiguration;
+use std::ops::Range;
+
 use bevy::{
     prelude::*,
     render::{primitives::Aabb, Extract},
@@ -9,33 +15,136 @@ use bevy::{
 ///
 /// The extraction only happens on change or creation of the entity,
 /// so it normally doesn't come at a high performance cost.
+/// If only the heights of some blades changed, for example by mowing, just those heights are updated.
 ///
-/// Note:
-/// 1) Currently, the grass data extracted in the render world doesn't get freed when the grass entity is deleted.
-/// 2) If you are changing your grass data constantly you might run into performance problems rather quickly
+/// Note: If you are changing your grass data constantly you might run into performance problems rather quickly
 #[allow(clippy::type_complexity)]
 pub(crate) fn extract_grass(
     mut commands: Commands,
     grass_spawner: Extract<
-        Query<
-            (Entity, &GrassSpawner, &GlobalTransform, &Aabb),
-            Or<(Changed<GrassSpawner>, Changed<Aabb>)>,
-        >,
+        Query<(Entity, &GrassSpawner, Ref<Aabb>), Or<(Changed<GrassSpawner>, Changed<Aabb>)>>,
     >,
     mut grass_cache: ResMut<GrassCache>,
 ) {
-    for (entity, spawner, global_transform, aabb) in grass_spawner.iter() {
+    for (entity, spawner, aabb) in grass_spawner.iter() {
         let cache_value = grass_cache.entry(entity).or_default();
-        cache_value.transform = *global_transform;
+        if !aabb.is_changed() {
+            match (
+                &spawner.update,
+                &spawner.heights,
+                &cache_value.height_texture,
+            ) {
+                (GrassUpdate::Unchanged, _, _) => continue,
+                // if only a few heights changed, they can be written in the existing texture
+                (GrassUpdate::Heights(range), HeightRepresentation::PerBlade(heights), Some(_)) => {
+                    commands
+                        .spawn(HeightUpdate::new(heights, range.clone()))
+                        .insert(EntityStore(entity));
+                    continue;
+                }
+                // spawners which changed without their methods, for example through reflection, are prepared again
+                _ => {}
+            }
+        }
+        // all textures of the chunk are created again
+        cache_value.gpu_memory = 0;
         commands
             .spawn(spawner.clone())
             .insert(EntityStore(entity))
             .insert(*aabb);
     }
 }
+/// Removes the chunks of despawned grass entities from the [`GrassCache`], which frees their gpu data.
+pub(crate) fn extract_removed_grass(
+    grass_spawner: Extract<Query<Entity, With<GrassSpawner>>>,
+    mut grass_cache: ResMut<GrassCache>,
+) {
+    // removed chunks aren't drawn, so the remaining chunks don't need new bind groups
+    grass_cache
+        .bypass_change_detection()
+        .retain(|entity, _| grass_spawner.contains(*entity));
+}
 #[derive(Clone, Component)]
 pub(crate) struct EntityStore(pub Entity);
+
+/// The heights of some rows of the height texture of a grass chunk.
+#[derive(Clone, Component)]
+pub(crate) struct HeightUpdate {
+    pub first_row: u32,
+    pub width: u32,
+    pub heights: Vec<f32>,
+}
+impl HeightUpdate {
+    /// Selects the rows of the height texture which contain the blades in the range
+    fn new(heights: &[f32], range: Range<usize>) -> Self {
+        // the texture has the same dimensions as the one created in the prepare stage
+        let width = (heights.len() as f32).sqrt() as usize + 1;
+        let first_row = range.start / width;
+        let last_row = (range.end - 1) / width;
+        let start = first_row * width;
+        let end = ((last_row + 1) * width).min(heights.len());
+        let mut row_heights = heights[start..end].to_vec();
+        row_heights.resize((last_row + 1 - first_row) * width, 0.);
+        HeightUpdate {
+            first_row: first_row as u32,
+            width: width as u32,
+            heights: row_heights,
+        }
+    }
+}
+/// Extracts the growth of all grass chunks into the render world.
+///
+/// The growth is written into the existing buffer of the chunk, so it doesn't require new bind groups.
+#[allow(clippy::type_complexity)]
+pub(crate) fn extract_grass_growth(
+    grasses: Extract<Query<(Entity, Option<&GrassGrowth>), With<GrassSpawner>>>,
+    mut grass_cache: ResMut<GrassCache>,
+) {
+    let grass_cache = grass_cache.bypass_change_detection();
+    for (entity, growth) in grasses.iter() {
+        let Some(chunk) = grass_cache.get_mut(&entity) else {
+            continue;
+        };
+        let growth = growth.map_or_else(ShaderGrowthConfiguration::default, Into::into);
+        if growth != chunk.growth {
+            chunk.growth = growth;
+            chunk.growth_changed = true;
+        }
+    }
+}
+/// Extracts the coverage of all grass chunks into the render world.
+///
+/// Chunks without their own [`GrassCoverage`] or [`GrassCoverageMask`] use the ones of the [`GrassConfiguration`].
+#[allow(clippy::type_complexity)]
+pub(crate) fn extract_grass_coverage(
+    config: Extract<Res<GrassConfiguration>>,
+    grasses: Extract<
+        Query<(Entity, Option<&GrassCoverage>, Option<&GrassCoverageMask>), With<GrassSpawner>>,
+    >,
+    mut grass_cache: ResMut<GrassCache>,
+) {
+    let grass_cache = grass_cache.bypass_change_detection();
+    for (entity, coverage, mask) in grasses.iter() {
+        let Some(chunk) = grass_cache.get_mut(&entity) else {
+            continue;
+        };
+        let mut coverage = ShaderCoverageConfiguration::from(coverage.unwrap_or(&config.coverage));
+        if let Some(mask) = mask.or(config.coverage_mask.as_ref()) {
+            coverage = coverage.with_mask(mask);
+        }
+        // the texture is bound by `prepare_uniform_buffers`
+        let mask_texture = mask.map(|mask| &mask.texture);
+        if mask_texture != chunk.coverage_mask.as_ref() {
+            chunk.coverage_mask = mask_texture.cloned();
+        }
+        if coverage != chunk.coverage {
+            chunk.coverage = coverage;
+            chunk.coverage_changed = true;
+        }
+    }
+}
 /// Extracts all visible grass entities into the render world.
+#[allow(clippy::type_complexity)]
 pub(crate) fn extract_visibility(
     visibility_queue: Extract<
         Query<(Entity, &ComputedVisibility), (With<GrassSpawner>, With<Transform>)>,
@@ -47,3 +156,187 @@ pub(crate) fn extract_visibility(
         .filter_map(|(e, visibility)| visibility.is_visible().then_some(e))
         .collect();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grass::GrassBlade;
+    use crate::grass_spawner::{add_aabb_box_to_grass, clear_grass_updates, GrassSpawnerFlags};
+    use crate::height_map::HeightMap;
+    use crate::region::GrassRegion;
+    use bevy::{
+        ecs::entity::EntityMap,
+        render::MainWorld,
+        scene::{serde::SceneDeserializer, DynamicScene},
+    };
+    use serde::de::DeserializeSeed;
+
+    fn type_registry() -> AppTypeRegistry {
+        let type_registry = AppTypeRegistry::default();
+        {
+            let mut registry = type_registry.write();
+            // the math types are registered by the `TypeRegistrationPlugin` in an app
+            registry.register::<f32>();
+            registry.register::<Vec2>();
+            registry.register::<Vec4>();
+            registry.register::<GrassSpawner>();
+            registry.register::<GrassSpawnerFlags>();
+            registry.register::<HeightRepresentation>();
+            registry.register::<Vec<Vec2>>();
+            registry.register::<Vec<Vec4>>();
+            registry.register::<Vec<f32>>();
+            registry.register::<Option<Vec<f32>>>();
+            registry.register::<Option<HeightMap>>();
+            registry.register::<Option<Handle<Image>>>();
+        }
+        type_registry
+    }
+    fn spawner(blade_count: usize) -> GrassSpawner {
+        let positions = (0..blade_count)
+            .map(|i| Vec3::new(i as f32, 0., 1.))
+            .collect();
+        GrassSpawner::new()
+            .with_positions(positions)
+            .with_heights(vec![1.; blade_count])
+    }
+    /// Saves the spawner in a scene and loads the scene again, like a scene file would be
+    fn save_and_load_scene(spawner: GrassSpawner, type_registry: &AppTypeRegistry) -> DynamicScene {
+        let mut world = World::new();
+        world.spawn(spawner);
+        let scene = DynamicScene::from_world(&world, type_registry);
+        let ron = scene.serialize_ron(type_registry).unwrap();
+        let mut deserializer = ron::de::Deserializer::from_str(&ron).unwrap();
+        SceneDeserializer {
+            type_registry: &type_registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap()
+    }
+    /// The main and render world, with the systems needed to extract grass chunks
+    struct TestApp {
+        main_world: World,
+        render_world: World,
+        main_schedule: Schedule,
+        extract_schedule: Schedule,
+        first_schedule: Schedule,
+    }
+    impl TestApp {
+        fn new(type_registry: AppTypeRegistry) -> Self {
+            let mut main_world = World::new();
+            main_world.insert_resource(type_registry);
+            let mut main_schedule = Schedule::new();
+            main_schedule.add_system(add_aabb_box_to_grass);
+            let mut first_schedule = Schedule::new();
+            first_schedule.add_system(clear_grass_updates);
+            let mut render_world = World::new();
+            render_world.init_resource::<GrassCache>();
+            let mut extract_schedule = Schedule::new();
+            extract_schedule.add_system(extract_removed_grass.before(extract_grass));
+            extract_schedule.add_system(extract_grass);
+            TestApp {
+                main_world,
+                render_world,
+                main_schedule,
+                extract_schedule,
+                first_schedule,
+            }
+        }
+        /// Runs a frame of the main world and extracts it into the render world.
+        ///
+        /// Returns the chunks spawned for the prepare systems with their blade count
+        fn run_frame(&mut self) -> Vec<(Entity, usize)> {
+            self.main_schedule.run(&mut self.main_world);
+            // the extraction reads the main world from a resource, like in the render app
+            let mut main_world = MainWorld::default();
+            std::mem::swap(&mut *main_world, &mut self.main_world);
+            self.render_world.insert_resource(main_world);
+            self.extract_schedule.run(&mut self.render_world);
+            let mut main_world = self.render_world.remove_resource::<MainWorld>().unwrap();
+            std::mem::swap(&mut *main_world, &mut self.main_world);
+            self.main_world.clear_trackers();
+            // runs at the start of the next frame, before the spawners are changed again
+            self.first_schedule.run(&mut self.main_world);
+
+            let render_world = &mut self.render_world;
+            let prepared = render_world
+                .query::<(&GrassSpawner, &EntityStore, &Aabb)>()
+                .iter(render_world)
+                .map(|(spawner, EntityStore(entity), _)| (*entity, spawner.blade_count()))
+                .collect();
+            // the render world is cleared each frame
+            render_world.clear_entities();
+            prepared
+        }
+    }
+
+    #[test]
+    fn chunks_loaded_from_scenes_are_extracted() {
+        let type_registry = type_registry();
+        let mut app = TestApp::new(type_registry.clone());
+
+        let mut entity_map = EntityMap::default();
+        save_and_load_scene(spawner(3), &type_registry)
+            .write_to_world(&mut app.main_world, &mut entity_map)
+            .unwrap();
+        let chunk = entity_map.values().next().unwrap();
+        assert_eq!(app.run_frame(), vec![(chunk, 3)]);
+        assert!(app
+            .render_world
+            .resource::<GrassCache>()
+            .contains_key(&chunk));
+        // unchanged chunks aren't extracted again
+        assert_eq!(app.run_frame(), vec![]);
+
+        // loading the scene again changes the existing spawner through reflection.
+        // The aabb stays the same, so only the changed spawner causes the extraction
+        let colored_spawner = spawner(3).with_colors(vec![Color::RED; 3]);
+        save_and_load_scene(colored_spawner, &type_registry)
+            .write_to_world(&mut app.main_world, &mut entity_map)
+            .unwrap();
+        assert_eq!(app.run_frame(), vec![(chunk, 3)]);
+        assert_eq!(app.run_frame(), vec![]);
+
+        // mowing next to the chunk changes the spawner, but none of its blades
+        let far_away = GrassRegion::Circle {
+            center: Vec2::splat(100.),
+            radius: 1.,
+        };
+        let mut spawner = app.main_world.get_mut::<GrassSpawner>(chunk).unwrap();
+        spawner.mow(&far_away, 0.5);
+        assert_eq!(app.run_frame(), vec![]);
+    }
+
+    #[test]
+    fn despawned_chunks_are_removed_from_the_cache() {
+        let mut app = TestApp::new(type_registry());
+        let kept = app.main_world.spawn(spawner(2)).id();
+        let despawned = app.main_world.spawn(spawner(3)).id();
+        app.run_frame();
+        assert_eq!(app.render_world.resource::<GrassCache>().len(), 2);
+
+        app.main_world.despawn(despawned);
+        app.run_frame();
+        let cache = app.render_world.resource::<GrassCache>();
+        assert!(cache.contains_key(&kept));
+        assert!(!cache.contains_key(&despawned));
+    }
+
+    #[test]
+    fn empty_chunks_are_filled_by_appending_blades() {
+        let mut app = TestApp::new(type_registry());
+        let chunk = app.main_world.spawn(GrassSpawner::new()).id();
+        // the chunk is prepared without instances, so nothing is drawn
+        assert_eq!(app.run_frame(), vec![(chunk, 0)]);
+        assert_eq!(app.run_frame(), vec![]);
+
+        let blades = (0..5).map(|i| GrassBlade {
+            position: Vec3::new(i as f32, 1., 0.),
+            height: 1.,
+        });
+        let mut spawner = app.main_world.get_mut::<GrassSpawner>(chunk).unwrap();
+        spawner.append_blades(blades);
+        assert_eq!(app.run_frame(), vec![(chunk, 5)]);
+        let aabb = app.main_world.get::<Aabb>(chunk).unwrap();
+        assert_eq!(aabb.center.x, 2.);
+    }
+}
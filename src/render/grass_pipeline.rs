@@ -13,7 +13,7 @@ use bevy::{
 };
 
 use crate::{grass_spawner::GrassSpawnerFlags, warblers_plugin::GRASS_SHADER_HANDLE};
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct GrassPipeline {
     shader: Handle<Shader>,
     mesh_pipeline: MeshPipeline,
@@ -52,6 +52,39 @@ impl FromWorld for GrassPipeline {
                     },
                     count: None,
                 },
+                // Trail map Texture
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // State map Texture
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Coverage mask Texture
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
             ],
         });
         let height_map_layout =
@@ -97,17 +130,64 @@ impl FromWorld for GrassPipeline {
                 }],
             });
         let height_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: Some("warbler_grass explicit y layout"),
-            entries: &[BindGroupLayoutEntry {
-                binding: 0,
-                visibility: ShaderStages::VERTEX,
-                ty: BindingType::Texture {
-                    sample_type: TextureSampleType::Float { filterable: false },
-                    view_dimension: TextureViewDimension::D2,
-                    multisampled: false,
+            label: Some("warbler_grass height layout"),
+            entries: &[
+                // heights
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
                 },
-                count: None,
-            }],
+                // colors
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // growth
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // growth delays
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // coverage
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
         });
         let explicit_xz_layout =
             render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
@@ -158,6 +238,12 @@ impl SpecializedMeshPipeline for GrassPipeline {
         } else {
             descriptor.layout.push(self.explicit_y_layout.clone());
         }
+        if key.flags.contains(GrassSpawnerFlags::COLOR_DEFINED) {
+            vertex.shader_defs.push("PER_BLADE_COLOR".into());
+        }
+        if key.flags.contains(GrassSpawnerFlags::GROWTH_DELAYS) {
+            vertex.shader_defs.push("PER_BLADE_GROWTH".into());
+        }
         if !key.flags.contains(GrassSpawnerFlags::DENSITY_MAP) {
             descriptor.layout.push(self.explicit_xz_layout.clone());
             descriptor.layout.push(self.height_layout.clone());
@@ -1,45 +1,96 @@
 use std::mem;
 use std::num::NonZeroU32;
-use std::ops::Mul;
 
-use super::extract::EntityStore;
+use super::extract::{EntityStore, HeightUpdate};
 use super::grass_pipeline::GrassPipeline;
+use crate::clock::GrassClock;
+use crate::coverage::{GrassCoverage, GrassCoverageMask};
+use crate::diagnostic::GrassRenderStats;
 use crate::grass_spawner::{GrassSpawner, GrassSpawnerFlags, HeightRepresentation};
+use crate::growth::GrassGrowth;
+use crate::height_map::{HeightMap, HeightMapExtent, HeightMapFilter};
 use crate::render::cache::GrassCache;
+use crate::state_map::GrassStateMap;
+use crate::trail_map::GrassTrailMap;
+use crate::wind::{wind_noise_offset, GRASS_WIND_TEXTURE_HANDLE};
 use crate::GrassConfiguration;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy::render::primitives::Aabb;
 use bevy::render::render_asset::RenderAssets;
 use bevy::render::render_resource::{
-    BindGroupDescriptor, BindGroupEntry, BindingResource, BufferBinding, BufferInitDescriptor,
-    BufferUsages, Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d, ShaderType, TextureAspect,
-    TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView,
-    TextureViewDescriptor, TextureViewDimension, TextureViewId,
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindingResource, Buffer, BufferBinding,
+    BufferInitDescriptor, BufferUsages, Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d,
+    Texture, TextureAspect, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    TextureView, TextureViewDescriptor, TextureViewDimension, TextureViewId,
 };
 use bevy::render::renderer::{RenderDevice, RenderQueue};
-use bevy::render::texture::FallbackImage;
+use bevy::render::texture::{FallbackImage, GpuImage};
 use bytemuck::{Pod, Zeroable};
 
+/// Uploads grass data to the gpu and counts the uploaded bytes for the
+/// [`GrassDiagnosticsPlugin`](crate::diagnostic::GrassDiagnosticsPlugin).
+///
+/// All buffers and textures of the grass are written through it, so no upload is missed or counted twice
+#[derive(SystemParam)]
+pub(crate) struct GrassUploads<'w> {
+    render_device: Res<'w, RenderDevice>,
+    render_queue: Res<'w, RenderQueue>,
+    stats: Res<'w, GrassRenderStats>,
+}
+impl<'w> GrassUploads<'w> {
+    /// Creates a uniform buffer, which can be written again later
+    fn create_uniform_buffer(&self, label: &str, contents: &[u8]) -> Buffer {
+        self.stats.add_upload(contents.len() as u64);
+        self.render_device
+            .create_buffer_with_data(&BufferInitDescriptor {
+                label: Some(label),
+                contents,
+                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+            })
+    }
+    fn write_buffer(&self, buffer: &Buffer, data: &[u8]) {
+        self.stats.add_upload(data.len() as u64);
+        self.render_queue.write_buffer(buffer, 0, data);
+    }
+    fn write_texture(
+        &self,
+        texture: ImageCopyTexture,
+        data: &[u8],
+        data_layout: ImageDataLayout,
+        size: Extent3d,
+    ) {
+        self.stats.add_upload(data.len() as u64);
+        self.render_queue
+            .write_texture(texture, data, data_layout, size);
+    }
+    /// Counts the upload of an image used by the grass, which is uploaded by bevy
+    fn add_image(&self, image: &GpuImage) {
+        let info = image.texture_format.describe();
+        let (block_width, block_height) = info.block_dimensions;
+        let blocks_x = (image.size.x as u64).div_ceil(block_width as u64);
+        let blocks_y = (image.size.y as u64).div_ceil(block_height as u64);
+        self.stats
+            .add_upload(blocks_x * blocks_y * info.block_size as u64);
+    }
+}
+
 pub(crate) fn prepare_explicit_xz_buffer(
     mut cache: ResMut<GrassCache>,
     pipeline: Res<GrassPipeline>,
     render_device: Res<RenderDevice>,
-    render_queue: Res<RenderQueue>,
+    uploads: GrassUploads,
     mut inserted_grass: Query<(&mut GrassSpawner, &EntityStore)>,
 ) {
     for (mut spawner, EntityStore(id)) in inserted_grass.iter_mut() {
-        if !spawner.flags.contains(GrassSpawnerFlags::XZ_DEFINED) {
-            panic!("Cannot spawn grass without the xz-positions defined");
-        }
-
         if let Some(chunk) = cache.get_mut(id) {
             chunk.instance_count = spawner.positions_xz.len();
-            let view = prepare_texture_from_data(
+            let (_, view) = prepare_texture_from_data(
                 &mut spawner.positions_xz,
-                &render_device,
-                &render_queue,
+                &uploads,
                 TextureFormat::Rg32Float,
             );
+            chunk.add_gpu_memory(data_size(&spawner.positions_xz));
             let layout = pipeline.explicit_xz_layout.clone();
             let bind_group_descriptor = BindGroupDescriptor {
                 label: Some("grass explicit y positions bind group"),
@@ -65,39 +116,105 @@ pub(crate) fn prepare_height_buffer(
     mut cache: ResMut<GrassCache>,
     pipeline: Res<GrassPipeline>,
     render_device: Res<RenderDevice>,
-    render_queue: Res<RenderQueue>,
+    uploads: GrassUploads,
+    fallback_img: Res<FallbackImage>,
     mut inserted_grass: Query<(&mut GrassSpawner, &EntityStore)>,
 ) {
     for (mut spawner, EntityStore(id)) in inserted_grass.iter_mut() {
         if let Some(chunk) = cache.get_mut(id) {
-            let view = match &mut spawner.heights {
+            let (texture, view) = match &mut spawner.heights {
                 HeightRepresentation::Uniform(height) => {
                     let mut heights = vec![*height; spawner.positions_xz.len()];
-                    prepare_texture_from_data(
-                        &mut heights,
-                        &render_device,
-                        &render_queue,
-                        TextureFormat::R32Float,
-                    )
+                    let texture =
+                        prepare_texture_from_data(&mut heights, &uploads, TextureFormat::R32Float);
+                    chunk.add_gpu_memory(data_size(&heights));
+                    texture
                 }
-                HeightRepresentation::PerBlade(heights) => prepare_texture_from_data(
-                    heights,
-                    &render_device,
-                    &render_queue,
+                HeightRepresentation::PerBlade(heights) => {
+                    let texture =
+                        prepare_texture_from_data(heights, &uploads, TextureFormat::R32Float);
+                    chunk.add_gpu_memory(data_size(heights));
+                    texture
+                }
+            };
+            // the colors are only read by the shader if they are defined
+            let color_view = if spawner.colors.is_empty() {
+                None
+            } else {
+                let (_, color_view) = prepare_texture_from_data(
+                    &mut spawner.colors,
+                    &uploads,
+                    TextureFormat::Rgba32Float,
+                );
+                chunk.add_gpu_memory(data_size(&spawner.colors));
+                Some(color_view)
+            };
+            let growth_buffer = uploads
+                .create_uniform_buffer("grass growth buffer", bytemuck::bytes_of(&chunk.growth));
+            chunk.add_gpu_memory(mem::size_of::<ShaderGrowthConfiguration>() as u64);
+            let coverage_buffer = uploads.create_uniform_buffer(
+                "grass coverage buffer",
+                bytemuck::bytes_of(&chunk.coverage),
+            );
+            chunk.add_gpu_memory(mem::size_of::<ShaderCoverageConfiguration>() as u64);
+            // the delays are only read by the shader if they are defined
+            let delay_view = if spawner.growth_delays.is_empty() {
+                None
+            } else {
+                let (_, delay_view) = prepare_texture_from_data(
+                    &mut spawner.growth_delays,
+                    &uploads,
                     TextureFormat::R32Float,
-                ),
+                );
+                chunk.add_gpu_memory(data_size(&spawner.growth_delays));
+                Some(delay_view)
             };
-            let layout = pipeline.explicit_xz_layout.clone();
+            let layout = pipeline.height_layout.clone();
             let bind_group_descriptor = BindGroupDescriptor {
                 label: Some("grass height bind group"),
                 layout: &layout,
-                entries: &[BindGroupEntry {
-                    binding: 0,
-                    resource: BindingResource::TextureView(&view),
-                }],
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::TextureView(
+                            color_view.as_ref().unwrap_or(&fallback_img.texture_view),
+                        ),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: BindingResource::Buffer(BufferBinding {
+                            buffer: &growth_buffer,
+                            offset: 0,
+                            size: None,
+                        }),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: BindingResource::TextureView(
+                            delay_view.as_ref().unwrap_or(&fallback_img.texture_view),
+                        ),
+                    },
+                    BindGroupEntry {
+                        binding: 4,
+                        resource: BindingResource::Buffer(BufferBinding {
+                            buffer: &coverage_buffer,
+                            offset: 0,
+                            size: None,
+                        }),
+                    },
+                ],
             };
             let bind_group = render_device.create_bind_group(&bind_group_descriptor);
             chunk.height_buffer = Some(bind_group);
+            chunk.height_texture = Some(texture);
+            chunk.growth_buffer = Some(growth_buffer);
+            chunk.growth_changed = false;
+            chunk.coverage_buffer = Some(coverage_buffer);
+            chunk.coverage_changed = false;
 
             chunk.flags = spawner.flags;
         } else {
@@ -107,27 +224,99 @@ pub(crate) fn prepare_height_buffer(
         }
     }
 }
+/// Writes the changed heights of grass chunks in their existing height texture
+pub(crate) fn prepare_height_updates(
+    mut cache: ResMut<GrassCache>,
+    uploads: GrassUploads,
+    updates: Query<(&HeightUpdate, &EntityStore)>,
+) {
+    // updating the heights doesn't require new bind groups
+    let cache = cache.bypass_change_detection();
+    for (update, EntityStore(id)) in updates.iter() {
+        let Some(chunk) = cache.get_mut(id) else {
+            warn!("Tried to update the heights of a grass chunk which wasn't prepared before");
+            continue;
+        };
+        let Some(texture) = chunk.height_texture.as_ref() else {
+            warn!("Tried to update the heights of a grass chunk which wasn't prepared before");
+            continue;
+        };
+        let rows = update.heights.len() as u32 / update.width;
+        uploads.write_texture(
+            ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: Origin3d {
+                    x: 0,
+                    y: update.first_row,
+                    z: 0,
+                },
+                aspect: TextureAspect::All,
+            },
+            bytemuck::cast_slice(update.heights.as_slice()),
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(mem::size_of::<f32>() as u32 * update.width),
+                rows_per_image: NonZeroU32::new(rows),
+            },
+            Extent3d {
+                width: update.width,
+                height: rows,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+}
+/// Writes the changed growth of grass chunks in their existing growth buffer
+pub(crate) fn prepare_growth_updates(mut cache: ResMut<GrassCache>, uploads: GrassUploads) {
+    // updating the growth doesn't require new bind groups
+    for chunk in cache.bypass_change_detection().values_mut() {
+        if !chunk.growth_changed {
+            continue;
+        }
+        let Some(buffer) = chunk.growth_buffer.as_ref() else {
+            continue;
+        };
+        uploads.write_buffer(buffer, bytemuck::bytes_of(&chunk.growth));
+        chunk.growth_changed = false;
+    }
+}
+/// Writes the changed coverage of grass chunks in their existing coverage buffer
+pub(crate) fn prepare_coverage_updates(mut cache: ResMut<GrassCache>, uploads: GrassUploads) {
+    // updating the coverage doesn't require new bind groups
+    for chunk in cache.bypass_change_detection().values_mut() {
+        if !chunk.coverage_changed {
+            continue;
+        }
+        let Some(buffer) = chunk.coverage_buffer.as_ref() else {
+            continue;
+        };
+        uploads.write_buffer(buffer, bytemuck::bytes_of(&chunk.coverage));
+        chunk.coverage_changed = false;
+    }
+}
 pub(crate) fn prepare_explicit_y_buffer(
     mut cache: ResMut<GrassCache>,
     render_device: Res<RenderDevice>,
-    render_queue: Res<RenderQueue>,
+    uploads: GrassUploads,
     pipeline: Res<GrassPipeline>,
     mut inserted_grass: Query<(&mut GrassSpawner, &EntityStore)>,
 ) {
     for (mut spawner, EntityStore(id)) in inserted_grass.iter_mut() {
-        if !spawner.flags.contains(GrassSpawnerFlags::Y_DEFINED) {
+        // empty chunks don't need any positions
+        if !spawner.flags.contains(GrassSpawnerFlags::Y_DEFINED) && !spawner.is_empty() {
             panic!("Cannot spawn grass without the y-positions defined");
         }
         if spawner.flags.contains(GrassSpawnerFlags::HEIGHT_MAP) {
             continue;
         }
         if let Some(chunk) = cache.get_mut(id) {
-            let view = prepare_texture_from_data(
+            let (_, view) = prepare_texture_from_data(
                 &mut spawner.positions_y,
-                &render_device,
-                &render_queue,
+                &uploads,
                 TextureFormat::R32Float,
             );
+            chunk.add_gpu_memory(data_size(&spawner.positions_y));
             let layout = pipeline.explicit_y_layout.clone();
             let bind_group_descriptor = BindGroupDescriptor {
                 label: Some("grass explicit y positions bind group"),
@@ -147,47 +336,29 @@ pub(crate) fn prepare_explicit_y_buffer(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn prepare_height_map_buffer(
     mut cache: ResMut<GrassCache>,
     render_device: Res<RenderDevice>,
+    uploads: GrassUploads,
     pipeline: Res<GrassPipeline>,
     fallback_img: Res<FallbackImage>,
     images: Res<RenderAssets<Image>>,
     inserted_grass: Query<(&GrassSpawner, &EntityStore, &Aabb)>,
-    mut local_height_map_buffer: Local<Vec<(EntityStore, Handle<Image>, Aabb)>>,
+    mut local_height_map_buffer: Local<Vec<(EntityStore, Handle<Image>, Buffer)>>,
 ) {
     let mut to_remove = Vec::new();
 
-    for (EntityStore(e), handle, aabb) in local_height_map_buffer.iter() {
+    for (EntityStore(e), handle, config_buffer) in local_height_map_buffer.iter() {
         if let Some(tex) = images.get(handle) {
             to_remove.push(*e);
-            let height_map_texture = &tex.texture_view;
-            let aabb_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
-                label: Some("aabb buffer"),
-                contents: bytemuck::bytes_of(&aabb.half_extents.mul(2.).as_dvec3().as_vec3()),
-                usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-            });
-            let layout = pipeline.height_map_layout.clone();
-            let bind_group_descriptor = BindGroupDescriptor {
-                label: Some("grass height map bind group"),
-                layout: &layout,
-                entries: &[
-                    BindGroupEntry {
-                        binding: 0,
-                        resource: BindingResource::TextureView(height_map_texture),
-                    },
-                    BindGroupEntry {
-                        binding: 1,
-                        resource: BindingResource::Buffer(BufferBinding {
-                            buffer: &aabb_buffer,
-                            offset: 0,
-                            size: None,
-                        }),
-                    },
-                ],
-            };
-
-            let bind_group = render_device.create_bind_group(&bind_group_descriptor);
+            // the config buffer was already uploaded with the fallback image
+            let bind_group = create_height_map_bind_group(
+                &render_device,
+                &pipeline,
+                &tex.texture_view,
+                config_buffer,
+            );
             if let Some(chunk) = cache.get_mut(e) {
                 chunk.height_map = Some(bind_group);
             } else {
@@ -198,124 +369,405 @@ pub(crate) fn prepare_height_map_buffer(
     local_height_map_buffer.retain(|map| !to_remove.contains(&map.0 .0));
     for (spawner, entity_store, aabb) in inserted_grass.iter() {
         let id = entity_store.0;
-        if spawner.flags.contains(GrassSpawnerFlags::HEIGHT_MAP) {
-            let handle = &spawner.height_map.as_ref().unwrap().height_map;
-            if images.get(handle).is_none() {
-                local_height_map_buffer.push((entity_store.clone(), handle.clone(), *aabb));
-            }
-        }
-        let (height_map_texture, aabb_buffer) =
-            if !spawner.flags.contains(GrassSpawnerFlags::HEIGHT_MAP) {
-                let height_map_texture = &fallback_img.texture_view;
-                let aabb_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
-                    label: Some("aabb buffer"),
-                    contents: &[0],
-                    usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-                });
-                (height_map_texture, aabb_buffer)
-            } else {
-                let handle = spawner.height_map.as_ref().unwrap().height_map.clone();
-                let height_map_texture = if let Some(tex) = images.get(&handle) {
+        let height_map = spawner
+            .height_map
+            .as_ref()
+            .filter(|_| spawner.flags.contains(GrassSpawnerFlags::HEIGHT_MAP));
+        let config = height_map.map_or_else(ShaderHeightMapConfiguration::zeroed, |height_map| {
+            ShaderHeightMapConfiguration::new(height_map, aabb)
+        });
+        let config_buffer =
+            uploads.create_uniform_buffer("height map config buffer", bytemuck::bytes_of(&config));
+        let height_map_texture = match height_map {
+            Some(height_map) => {
+                if let Some(tex) = images.get(&height_map.height_map) {
                     &tex.texture_view
                 } else {
+                    // the height map gets prepared again as soon as the texture is loaded
+                    local_height_map_buffer.push((
+                        entity_store.clone(),
+                        height_map.height_map.clone(),
+                        config_buffer.clone(),
+                    ));
                     &fallback_img.texture_view
-                };
-
-                let aabb_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
-                    label: Some("aabb buffer"),
-                    contents: bytemuck::bytes_of(&aabb.half_extents.mul(2.).as_dvec3().as_vec3()),
-                    usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-                });
-                (height_map_texture, aabb_buffer)
-            };
-        let layout = pipeline.height_map_layout.clone();
-
-        let bind_group_descriptor = BindGroupDescriptor {
-            label: Some("grass height map bind group"),
-            layout: &layout,
-            entries: &[
-                BindGroupEntry {
-                    binding: 0,
-                    resource: BindingResource::TextureView(height_map_texture),
-                },
-                BindGroupEntry {
-                    binding: 1,
-                    resource: BindingResource::Buffer(BufferBinding {
-                        buffer: &aabb_buffer,
-                        offset: 0,
-                        size: None,
-                    }),
-                },
-            ],
+                }
+            }
+            None => &fallback_img.texture_view,
         };
-
-        let bind_group = render_device.create_bind_group(&bind_group_descriptor);
+        let bind_group = create_height_map_bind_group(
+            &render_device,
+            &pipeline,
+            height_map_texture,
+            &config_buffer,
+        );
         if let Some(chunk) = cache.get_mut(&id) {
+            chunk.add_gpu_memory(mem::size_of::<ShaderHeightMapConfiguration>() as u64);
             chunk.height_map = Some(bind_group);
         } else {
             warn!("Tried to prepare a buffer for a grass chunk which wasn't registered before");
         }
     }
 }
-pub(crate) fn prepare_uniform_buffers(
-    pipeline: Res<GrassPipeline>,
-    mut cache: ResMut<GrassCache>,
-    region_config: Res<GrassConfiguration>,
-    fallback_img: Res<FallbackImage>,
-    render_device: Res<RenderDevice>,
-    images: Res<RenderAssets<Image>>,
-    mut last_texture_id: Local<Option<TextureViewId>>,
-) {
-    let texture = &images
-        .get(&region_config.wind_noise_texture)
-        .unwrap_or(&fallback_img)
-        .texture_view;
-    if !region_config.is_changed() && Some(texture.id()) == *last_texture_id && !cache.is_changed()
-    {
-        return;
-    }
-    *last_texture_id = Some(texture.id());
-
-    let shader_config = ShaderRegionConfiguration::from(region_config.as_ref());
-    let config_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
-        label: Some("region config buffer"),
-        contents: bytemuck::bytes_of(&shader_config),
-        usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-    });
-
-    let layout = pipeline.region_layout.clone();
+fn create_height_map_bind_group(
+    render_device: &RenderDevice,
+    pipeline: &GrassPipeline,
+    height_map_texture: &TextureView,
+    config_buffer: &Buffer,
+) -> BindGroup {
     let bind_group_descriptor = BindGroupDescriptor {
-        label: Some("grass uniform bind group"),
-        layout: &layout,
+        label: Some("grass height map bind group"),
+        layout: &pipeline.height_map_layout,
         entries: &[
             BindGroupEntry {
                 binding: 0,
+                resource: BindingResource::TextureView(height_map_texture),
+            },
+            BindGroupEntry {
+                binding: 1,
                 resource: BindingResource::Buffer(BufferBinding {
-                    buffer: &config_buffer,
+                    buffer: config_buffer,
                     offset: 0,
                     size: None,
                 }),
             },
-            BindGroupEntry {
-                binding: 1,
-                resource: BindingResource::TextureView(texture),
-            },
         ],
     };
-    let bind_group = render_device.create_bind_group(&bind_group_descriptor);
+    render_device.create_bind_group(&bind_group_descriptor)
+}
 
-    for instance_data in cache.values_mut() {
-        instance_data.uniform_bindgroup = Some(bind_group.clone());
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub(crate) struct ShaderHeightMapConfiguration {
+    origin: Vec2,
+    size: Vec2,
+    /// Selects the channel containing the heights
+    channel_mask: Vec4,
+    height: f32,
+    base_offset: f32,
+    /// 0 for nearest and 1 for bilinear filtering
+    filter_mode: u32,
+    /// Wasm requires shader uniforms to be aligned to 16 bytes
+    _wasm_padding: f32,
+}
+impl ShaderHeightMapConfiguration {
+    fn new(height_map: &HeightMap, aabb: &Aabb) -> Self {
+        let HeightMapExtent { origin, size } = height_map.extent_in(aabb);
+        let filter_mode = match height_map.filter {
+            HeightMapFilter::Nearest => 0,
+            HeightMapFilter::Bilinear => 1,
+        };
+        let mut channel_mask = Vec4::ZERO;
+        channel_mask[height_map.channel.index()] = 1.;
+        ShaderHeightMapConfiguration {
+            origin,
+            size,
+            channel_mask,
+            height: height_map.height,
+            base_offset: height_map.base_offset,
+            filter_mode,
+            _wasm_padding: 0.,
+        }
+    }
+}
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub(crate) struct ShaderGrowthConfiguration {
+    elapsed: f32,
+    duration: f32,
+    /// The index of the [`GrowthEasing`](crate::growth::GrowthEasing)
+    easing: u32,
+    /// Wasm requires shader uniforms to be aligned to 16 bytes
+    _wasm_padding: f32,
+}
+impl Default for ShaderGrowthConfiguration {
+    /// A chunk without growth is fully grown from the start
+    fn default() -> Self {
+        ShaderGrowthConfiguration {
+            elapsed: f32::MAX,
+            duration: 0.,
+            easing: 0,
+            _wasm_padding: 0.,
+        }
+    }
+}
+impl From<&GrassGrowth> for ShaderGrowthConfiguration {
+    fn from(growth: &GrassGrowth) -> Self {
+        ShaderGrowthConfiguration {
+            elapsed: growth.elapsed,
+            duration: growth.duration,
+            easing: growth.easing.index(),
+            _wasm_padding: 0.,
+        }
+    }
+}
+#[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
+#[repr(C)]
+pub(crate) struct ShaderCoverageConfiguration {
+    color: Vec4,
+    amount: f32,
+    height_threshold: f32,
+    /// Wasm requires shader uniforms to be aligned to 16 bytes
+    _wasm_padding: Vec2,
+    /// The origin (xy) and size (zw) of the coverage mask.
+    ///
+    /// A size of zero disables the coverage mask
+    mask_area: Vec4,
+}
+impl From<&GrassCoverage> for ShaderCoverageConfiguration {
+    fn from(coverage: &GrassCoverage) -> Self {
+        ShaderCoverageConfiguration {
+            color: coverage.color.into(),
+            amount: coverage.amount,
+            height_threshold: coverage.height_threshold,
+            _wasm_padding: Vec2::ZERO,
+            mask_area: Vec4::ZERO,
+        }
+    }
+}
+impl ShaderCoverageConfiguration {
+    pub(crate) fn with_mask(mut self, mask: &GrassCoverageMask) -> Self {
+        self.mask_area = Vec4::new(mask.origin.x, mask.origin.y, mask.size.x, mask.size.y);
+        self
+    }
+}
+/// Returns which of the textures differ from the `last` textures bound by a system.
+///
+/// Bevy prepares an image again with a new texture when it is modified, for example when it is reloaded from disk,
+/// so this detects both images which are replaced and images which are modified.
+fn replaced_textures<const N: usize>(
+    last: Option<[TextureViewId; N]>,
+    current: [TextureViewId; N],
+) -> [bool; N] {
+    let mut replaced = [true; N];
+    if let Some(last) = last {
+        for i in 0..N {
+            replaced[i] = last[i] != current[i];
+        }
     }
+    replaced
 }
+/// Writes the current wind in the texture shared by all [`GrassWindMaterial`](crate::wind::GrassWindMaterial)s
+pub(crate) fn prepare_wind_texture(
+    config: Res<GrassConfiguration>,
+    clock: Res<GrassClock>,
+    images: Res<RenderAssets<Image>>,
+    fallback_img: Res<FallbackImage>,
+    uploads: GrassUploads,
+    mut last_texture_ids: Local<Option<[TextureViewId; 2]>>,
+) {
+    let Some(image) = images.get(&GRASS_WIND_TEXTURE_HANDLE.typed()) else {
+        return;
+    };
+    // the offset is wrapped into the noise texture the grass uses
+    let noise_image: &GpuImage = images
+        .get(&config.wind_noise_texture)
+        .unwrap_or(&fallback_img);
+    let texture_ids = [image.texture_view.id(), noise_image.texture_view.id()];
+    let replaced = replaced_textures(*last_texture_ids, texture_ids);
+    if !config.is_changed() && !clock.is_changed() && !replaced.contains(&true) {
+        return;
+    }
+    *last_texture_ids = Some(texture_ids);
+    let noise_offset = wind_noise_offset(config.wind, &clock, noise_image.size);
+    let texels = [
+        [config.wind.x, config.wind.y, noise_offset.x, noise_offset.y],
+        [clock.elapsed(), 0., 0., 0.],
+    ];
+    uploads.write_texture(
+        ImageCopyTexture {
+            texture: &image.texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        bytemuck::cast_slice(&texels),
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: NonZeroU32::new(mem::size_of_val(&texels) as u32),
+            rows_per_image: None,
+        },
+        Extent3d {
+            width: texels.len() as u32,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn prepare_uniform_buffers(
+    pipeline: Res<GrassPipeline>,
+    mut cache: ResMut<GrassCache>,
+    region_config: Res<GrassConfiguration>,
+    clock: Res<GrassClock>,
+    trail_map: Option<Res<GrassTrailMap>>,
+    state_map: Option<Res<GrassStateMap>>,
+    fallback_img: Res<FallbackImage>,
+    render_device: Res<RenderDevice>,
+    uploads: GrassUploads,
+    images: Res<RenderAssets<Image>>,
+    mut last_texture_ids: Local<Option<[TextureViewId; 4]>>,
+    mut config_buffer: Local<Option<Buffer>>,
+    mut region_bind_group: Local<Option<BindGroup>>,
+) {
+    let noise_image: &GpuImage = images
+        .get(&region_config.wind_noise_texture)
+        .unwrap_or(&fallback_img);
+    let trail_image: &GpuImage = trail_map
+        .as_ref()
+        .and_then(|trail_map| images.get(&trail_map.texture))
+        .unwrap_or(&fallback_img);
+    let state_image: &GpuImage = state_map
+        .as_ref()
+        .and_then(|state_map| images.get(&state_map.texture))
+        .unwrap_or(&fallback_img);
+    // masks which aren't loaded yet don't limit the coverage, since the fallback image is white
+    let mask_image: &GpuImage = region_config
+        .coverage_mask
+        .as_ref()
+        .and_then(|mask| images.get(&mask.texture))
+        .unwrap_or(&fallback_img);
+    let region_images = [noise_image, trail_image, state_image, mask_image];
+    let texture_ids = region_images.map(|image| image.texture_view.id());
+    let replaced = replaced_textures(*last_texture_ids, texture_ids);
+    // bevy uploads the images, they are counted once when a new texture of them is bound
+    let mut counted_textures = vec![fallback_img.texture_view.id()];
+    let mut count_image = |image: &GpuImage| {
+        let id = image.texture_view.id();
+        if !counted_textures.contains(&id) {
+            counted_textures.push(id);
+            uploads.add_image(image);
+        }
+    };
+    for (image, replaced) in region_images.into_iter().zip(replaced) {
+        if replaced {
+            count_image(image);
+        }
+    }
+    let (texture, trail_texture, state_texture, mask_texture) = (
+        &noise_image.texture_view,
+        &trail_image.texture_view,
+        &state_image.texture_view,
+        &mask_image.texture_view,
+    );
+    let trail_map_changed = trail_map
+        .as_ref()
+        .is_some_and(|trail_map| trail_map.is_changed());
+    let state_map_changed = state_map
+        .as_ref()
+        .is_some_and(|state_map| state_map.is_changed());
 
-#[derive(Debug, Clone, Copy, Pod, Zeroable, ShaderType)]
+    let mut shader_config = ShaderRegionConfiguration::from(region_config.as_ref())
+        .with_noise_offset(&clock, noise_image);
+    // the trail map is only used if its texture is already loaded
+    if let Some(trail_map) = trail_map.filter(|trail_map| images.contains_key(&trail_map.texture)) {
+        shader_config = shader_config.with_trail_map(&trail_map);
+    }
+    if let Some(state_map) = state_map.filter(|state_map| images.contains_key(&state_map.texture)) {
+        shader_config = shader_config.with_state_map(&state_map);
+    }
+    let rebuild = region_config.is_changed()
+        || trail_map_changed
+        || state_map_changed
+        || replaced.contains(&true)
+        || cache.is_changed();
+    if !rebuild {
+        // the time changes every frame, so it's written in the existing buffer
+        if let (true, Some(buffer)) = (clock.is_changed(), config_buffer.as_ref()) {
+            uploads.write_buffer(buffer, bytemuck::bytes_of(&shader_config));
+        }
+    } else {
+        *last_texture_ids = Some(texture_ids);
+        let buffer = uploads
+            .create_uniform_buffer("region config buffer", bytemuck::bytes_of(&shader_config));
+        *config_buffer = Some(buffer);
+    }
+    let Some(config_buffer) = config_buffer.as_ref() else {
+        return;
+    };
+    let create_bind_group = |mask_texture: &TextureView| {
+        let layout = pipeline.region_layout.clone();
+        let bind_group_descriptor = BindGroupDescriptor {
+            label: Some("grass uniform bind group"),
+            layout: &layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::Buffer(BufferBinding {
+                        buffer: config_buffer,
+                        offset: 0,
+                        size: None,
+                    }),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(texture),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(trail_texture),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindingResource::TextureView(state_texture),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindingResource::TextureView(mask_texture),
+                },
+            ],
+        };
+        render_device.create_bind_group(&bind_group_descriptor)
+    };
+    if rebuild {
+        *region_bind_group = Some(create_bind_group(mask_texture));
+    }
+    let Some(region_bind_group) = region_bind_group.as_ref() else {
+        return;
+    };
+    // chunks with their own coverage mask need their own bind group
+    for chunk in cache.bypass_change_detection().values_mut() {
+        let chunk_mask_image: Option<&GpuImage> = chunk
+            .coverage_mask
+            .as_ref()
+            .map(|mask| images.get(mask).unwrap_or(&fallback_img));
+        let mask_view = chunk_mask_image.map(|image| image.texture_view.id());
+        if !rebuild && mask_view == chunk.coverage_mask_view {
+            continue;
+        }
+        if let Some(mask_image) = chunk_mask_image.filter(|_| mask_view != chunk.coverage_mask_view)
+        {
+            count_image(mask_image);
+        }
+        chunk.uniform_bindgroup = Some(match chunk_mask_image {
+            Some(mask_image) => create_bind_group(&mask_image.texture_view),
+            None => region_bind_group.clone(),
+        });
+        chunk.coverage_mask_view = mask_view;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
 #[repr(C)]
 struct ShaderRegionConfiguration {
     main_color: Vec4,
     bottom_color: Vec4,
     wind: Vec2,
-    /// Wasm requires shader uniforms to be aligned to 16 bytes
+    /// How much trampled blades are flattened (x) and darkened (y)
+    trail_effect: Vec2,
+    /// The origin (xy) and size (zw) of the trail map.
+    ///
+    /// A size of zero disables the trail map
+    trail_area: Vec4,
+    /// The origin (xy) and size (zw) of the state map.
+    ///
+    /// A size of zero disables the state map
+    state_area: Vec4,
+    burnt_color: Vec4,
+    frost_color: Vec4,
+    /// How much burnt blades are shortened (x), wet blades are darkened (y) and wet tips are brightened (z)
+    state_effect: Vec4,
+    /// How far the wind moved over the noise texture, see [`wind_noise_offset`]
+    noise_offset: Vec2,
     _wasm_padding: Vec2,
 }
 
@@ -325,17 +777,60 @@ impl From<&GrassConfiguration> for ShaderRegionConfiguration {
             main_color: config.main_color.into(),
             bottom_color: config.bottom_color.into(),
             wind: config.wind,
+            trail_effect: Vec2::ZERO,
+            trail_area: Vec4::ZERO,
+            state_area: Vec4::ZERO,
+            burnt_color: Vec4::ZERO,
+            frost_color: Vec4::ZERO,
+            state_effect: Vec4::ZERO,
+            noise_offset: Vec2::ZERO,
             _wasm_padding: Vec2::ZERO,
         }
     }
 }
+impl ShaderRegionConfiguration {
+    fn with_noise_offset(mut self, clock: &GrassClock, noise_image: &GpuImage) -> Self {
+        self.noise_offset = wind_noise_offset(self.wind, clock, noise_image.size);
+        self
+    }
+    fn with_trail_map(mut self, trail_map: &GrassTrailMap) -> Self {
+        self.trail_effect = Vec2::new(trail_map.flatten, trail_map.darken);
+        self.trail_area = Vec4::new(
+            trail_map.origin.x,
+            trail_map.origin.y,
+            trail_map.size.x,
+            trail_map.size.y,
+        );
+        self
+    }
+    fn with_state_map(mut self, state_map: &GrassStateMap) -> Self {
+        self.state_area = Vec4::new(
+            state_map.origin.x,
+            state_map.origin.y,
+            state_map.size.x,
+            state_map.size.y,
+        );
+        self.burnt_color = state_map.burnt_color.into();
+        self.frost_color = state_map.frost_color.into();
+        self.state_effect = Vec4::new(
+            state_map.burnt_shrink,
+            state_map.wet_darken,
+            state_map.wet_gloss,
+            0.,
+        );
+        self
+    }
+}
+/// The number of bytes of the data
+fn data_size<T>(data: &[T]) -> u64 {
+    mem::size_of_val(data) as u64
+}
 fn prepare_texture_from_data<T: Default + Clone + bytemuck::Pod>(
     data: &mut Vec<T>,
-    render_device: &RenderDevice,
-    render_queue: &RenderQueue,
+    uploads: &GrassUploads,
     format: TextureFormat,
-) -> TextureView {
-    let device = render_device.wgpu_device();
+) -> (Texture, TextureView) {
+    let device = uploads.render_device.wgpu_device();
 
     // the dimensions of the texture are choosen to be nxn for the tiniest n which can contain the data
     let sqrt = (data.len() as f32).sqrt() as u32 + 1;
@@ -362,7 +857,7 @@ fn prepare_texture_from_data<T: Default + Clone + bytemuck::Pod>(
     let t_size = mem::size_of::<T>();
 
     // write data to texture
-    render_queue.write_texture(
+    uploads.write_texture(
         ImageCopyTexture {
             texture: &texture,
             mip_level: 0,
@@ -377,16 +872,46 @@ fn prepare_texture_from_data<T: Default + Clone + bytemuck::Pod>(
         },
         texture_size,
     );
-    texture
-        .create_view(&TextureViewDescriptor {
-            label: None,
-            format: Some(format),
-            dimension: Some(TextureViewDimension::D2),
-            aspect: TextureAspect::All,
-            base_mip_level: 0,
-            mip_level_count: NonZeroU32::new(1),
-            base_array_layer: 0,
-            array_layer_count: NonZeroU32::new(1),
-        })
-        .into()
+    let view = texture.create_view(&TextureViewDescriptor {
+        label: None,
+        format: Some(format),
+        dimension: Some(TextureViewDimension::D2),
+        aspect: TextureAspect::All,
+        base_mip_level: 0,
+        mip_level_count: NonZeroU32::new(1),
+        base_array_layer: 0,
+        array_layer_count: NonZeroU32::new(1),
+    });
+    (texture.into(), view.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modified_images_rebuild_the_bind_groups() {
+        let [noise, trail, state, mask] = [(); 4].map(|_| TextureViewId::new());
+        // nothing was bound yet
+        assert_eq!(
+            replaced_textures(None, [noise, trail, state, mask]),
+            [true; 4]
+        );
+        let last = Some([noise, trail, state, mask]);
+        assert_eq!(
+            replaced_textures(last, [noise, trail, state, mask]),
+            [false; 4]
+        );
+        // the render asset of a modified noise texture gets a new texture view
+        let modified_noise = TextureViewId::new();
+        assert_eq!(
+            replaced_textures(last, [modified_noise, trail, state, mask]),
+            [true, false, false, false]
+        );
+        // a mask which is loaded replaces the fallback image
+        assert_eq!(
+            replaced_textures(last, [noise, trail, state, TextureViewId::new()]),
+            [false, false, false, true]
+        );
+    }
 }
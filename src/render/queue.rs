@@ -3,12 +3,18 @@ use bevy::pbr::{MeshPipelineKey, MeshUniform};
 use bevy::prelude::*;
 use bevy::render::render_asset::RenderAssets;
 use bevy::render::render_phase::{DrawFunctions, RenderPhase};
-use bevy::render::render_resource::{PipelineCache, SpecializedMeshPipelines};
+use bevy::render::render_resource::{
+    CachedRenderPipelineId, PipelineCache, SpecializedMeshPipelines,
+};
 use bevy::render::view::ExtractedView;
+use bevy::utils::HashSet;
+use std::sync::atomic::Ordering;
 
 use super::cache::GrassCache;
 use super::grass_pipeline::{GrassPipeline, GrassRenderKey};
 use super::GrassDrawCall;
+use crate::diagnostic::GrassRenderStats;
+use crate::material::GrassMaterialInstance;
 
 #[allow(clippy::too_many_arguments)]
 pub fn queue_grass_buffers(
@@ -19,8 +25,11 @@ pub fn queue_grass_buffers(
     pipeline_cache: Res<PipelineCache>,
     grass_cacher: Res<GrassCache>,
     meshes: Res<RenderAssets<Mesh>>,
-    material_meshes: Query<(Entity, &MeshUniform, &Handle<Mesh>)>,
+    // chunks using a grass material are queued by the material plugin
+    material_meshes: Query<(Entity, &MeshUniform, &Handle<Mesh>), Without<GrassMaterialInstance>>,
     mut views: Query<(&ExtractedView, &mut RenderPhase<Opaque3d>)>,
+    stats: Res<GrassRenderStats>,
+    mut specialized_pipelines: Local<HashSet<CachedRenderPipelineId>>,
 ) {
     let draw_custom = opaque_3d_draw_functions
         .read()
@@ -34,7 +43,12 @@ pub fn queue_grass_buffers(
         let rangefinder = view.rangefinder3d();
         for (entity, mesh_uniform, mesh_handle) in material_meshes
             .iter()
-            .filter(|(e, _, _)| grass_cacher.contains_key(e))
+            // empty chunks aren't drawn
+            .filter(|(e, _, _)| {
+                grass_cacher
+                    .get(e)
+                    .is_some_and(|chunk| chunk.instance_count > 0)
+            })
         {
             if let Some(mesh) = meshes.get(mesh_handle) {
                 let mesh_key =
@@ -44,6 +58,9 @@ pub fn queue_grass_buffers(
                 let pipeline = pipelines
                     .specialize(&pipeline_cache, &grass_pipeline, grass_key, &mesh.layout)
                     .unwrap();
+                if specialized_pipelines.insert(pipeline) {
+                    stats.specializations.fetch_add(1, Ordering::Relaxed);
+                }
                 opaque_phase.add(Opaque3d {
                     entity,
                     pipeline,
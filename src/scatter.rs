@@ -0,0 +1,335 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, VertexAttributeValues},
+        render_resource::PrimitiveTopology,
+    },
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::region::GrassRegion;
+
+/// A point on the surface of a [`Mesh`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfacePoint {
+    /// The position of the point in the local space of the mesh
+    pub position: Vec3,
+    /// The normal of the surface at the point.
+    ///
+    /// The normals of the mesh are interpolated if the mesh has normals,
+    /// otherwise the normal of the triangle is used.
+    pub normal: Vec3,
+}
+
+/// Randomly distributes points on the surface of a [`Mesh`].
+///
+/// Larger triangles receive proportionally more points,
+/// so the points are evenly spread over the whole surface.
+/// `density` is the expected number of points per unit of area.
+/// The same `seed` always yields the same points.
+///
+/// Only meshes with a [`PrimitiveTopology::TriangleList`] are supported.
+pub fn scatter_on_mesh(mesh: &Mesh, density: f32, seed: u64) -> Vec<SurfacePoint> {
+    assert!(density >= 0.);
+    assert_eq!(
+        mesh.primitive_topology(),
+        PrimitiveTopology::TriangleList,
+        "Grass can only be scattered on meshes made of triangles"
+    );
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("Grass can only be scattered on meshes with `Float32x3` positions");
+    };
+    let normals = match mesh.attribute(Mesh::ATTRIBUTE_NORMAL) {
+        Some(VertexAttributeValues::Float32x3(normals)) => Some(normals),
+        _ => None,
+    };
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(Indices::U16(indices)) => indices.iter().map(|i| *i as usize).collect(),
+        Some(Indices::U32(indices)) => indices.iter().map(|i| *i as usize).collect(),
+        None => (0..positions.len()).collect(),
+    };
+    let triangles: Vec<[usize; 3]> = indices
+        .chunks_exact(3)
+        .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+        .collect();
+    // the cumulative area is used to choose triangles weighted by their area
+    let mut total_area = 0.;
+    let cumulative_areas: Vec<f32> = triangles
+        .iter()
+        .map(|[a, b, c]| {
+            let [a, b, c] = [positions[*a], positions[*b], positions[*c]].map(Vec3::from);
+            total_area += (b - a).cross(c - a).length() / 2.;
+            total_area
+        })
+        .collect();
+
+    if total_area <= 0. {
+        return Vec::new();
+    }
+
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let expected_count = total_area * density;
+    let mut count = expected_count as usize;
+    if rng.gen::<f32>() < expected_count.fract() {
+        count += 1;
+    }
+    (0..count)
+        .map(|_| {
+            let target = rng.gen::<f32>() * total_area;
+            let triangle_index = cumulative_areas
+                .partition_point(|area| *area < target)
+                .min(triangles.len() - 1);
+            let [a, b, c] = triangles[triangle_index];
+            // uniformly distributed barycentric coordinates
+            let r1 = rng.gen::<f32>().sqrt();
+            let r2 = rng.gen::<f32>();
+            let weights = Vec3::new(1. - r1, r1 * (1. - r2), r1 * r2);
+            let interpolate = |values: [Vec3; 3]| {
+                values[0] * weights.x + values[1] * weights.y + values[2] * weights.z
+            };
+            let corners = [positions[a], positions[b], positions[c]].map(Vec3::from);
+            let position = interpolate(corners);
+            let normal = match normals {
+                Some(normals) => interpolate([normals[a], normals[b], normals[c]].map(Vec3::from)),
+                None => (corners[1] - corners[0]).cross(corners[2] - corners[0]),
+            };
+            SurfacePoint {
+                position,
+                normal: normal.normalize_or_zero(),
+            }
+        })
+        .collect()
+}
+
+/// A pattern used to distribute grass blades in a [`GrassRegion`].
+///
+/// Regular grids produce visible rows of grass, especially when viewed at low angles.
+/// All patterns here avoid those rows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScatterPattern {
+    /// A grid in which each point is moved randomly inside its cell.
+    ///
+    /// `jitter` is the fraction of the cell the point can move in, in the range `[0, 1]`.
+    JitteredGrid { spacing: f32, jitter: f32 },
+    /// Random points which keep a distance of at least `min_distance` to each other.
+    ///
+    /// The points are placed as tightly as possible, resulting in a very natural distribution.
+    PoissonDisk { min_distance: f32 },
+    /// A fixed number of random points which are spread as evenly as possible.
+    BlueNoise { count: usize },
+}
+
+/// Distributes points in the region following the pattern.
+///
+/// The same `seed` always yields the same points.
+pub fn scatter_in_region(region: &GrassRegion, pattern: ScatterPattern, seed: u64) -> Vec<Vec2> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    match pattern {
+        ScatterPattern::JitteredGrid { spacing, jitter } => {
+            jittered_grid(region, spacing, jitter, &mut rng)
+        }
+        ScatterPattern::PoissonDisk { min_distance } => {
+            poisson_disk(region, min_distance, &mut rng)
+        }
+        ScatterPattern::BlueNoise { count } => blue_noise(region, count, &mut rng),
+    }
+}
+
+fn jittered_grid(region: &GrassRegion, spacing: f32, jitter: f32, rng: &mut SmallRng) -> Vec<Vec2> {
+    assert!(spacing > 0.);
+    let (min, max) = region.bounds();
+    let cells = ((max - min) / spacing).ceil().as_uvec2();
+    let mut points = Vec::new();
+    for x in 0..cells.x {
+        for y in 0..cells.y {
+            let offset = Vec2::new(rng.gen::<f32>(), rng.gen::<f32>()) - 0.5;
+            let cell_center = min + (UVec2::new(x, y).as_vec2() + 0.5) * spacing;
+            let point = cell_center + offset * jitter.clamp(0., 1.) * spacing;
+            if region.contains(point) {
+                points.push(point);
+            }
+        }
+    }
+    points
+}
+
+/// A grid used to look up points near a position
+struct PointGrid {
+    min: Vec2,
+    cell_size: f32,
+    size: UVec2,
+    cells: Vec<Vec<Vec2>>,
+}
+impl PointGrid {
+    fn new(min: Vec2, max: Vec2, cell_size: f32) -> Self {
+        let size = ((max - min) / cell_size).ceil().as_uvec2().max(UVec2::ONE);
+        PointGrid {
+            min,
+            cell_size,
+            size,
+            cells: vec![Vec::new(); (size.x * size.y) as usize],
+        }
+    }
+    fn cell(&self, point: Vec2) -> IVec2 {
+        ((point - self.min) / self.cell_size).floor().as_ivec2()
+    }
+    fn insert(&mut self, point: Vec2) {
+        let cell = self
+            .cell(point)
+            .clamp(IVec2::ZERO, self.size.as_ivec2() - 1);
+        self.cells[(cell.y as u32 * self.size.x + cell.x as u32) as usize].push(point);
+    }
+    /// Returns the squared distance to the closest point which is at most `ring` cells away
+    fn closest_distance_squared(&self, point: Vec2, ring: i32) -> f32 {
+        let center = self.cell(point);
+        let mut closest = f32::MAX;
+        for y in (center.y - ring).max(0)..=(center.y + ring).min(self.size.y as i32 - 1) {
+            for x in (center.x - ring).max(0)..=(center.x + ring).min(self.size.x as i32 - 1) {
+                for other in &self.cells[(y as u32 * self.size.x + x as u32) as usize] {
+                    closest = closest.min(point.distance_squared(*other));
+                }
+            }
+        }
+        closest
+    }
+}
+
+/// Returns a random point inside the region, or `None` if no point was found after many tries
+fn random_point_in(region: &GrassRegion, rng: &mut SmallRng) -> Option<Vec2> {
+    let (min, max) = region.bounds();
+    (0..1000)
+        .map(|_| min + Vec2::new(rng.gen(), rng.gen()) * (max - min))
+        .find(|point| region.contains(*point))
+}
+
+/// Poisson disk sampling following "Fast Poisson Disk Sampling in Arbitrary Dimensions" by Robert Bridson
+fn poisson_disk(region: &GrassRegion, min_distance: f32, rng: &mut SmallRng) -> Vec<Vec2> {
+    assert!(min_distance > 0.);
+    // number of candidates tried around each point
+    const CANDIDATES: usize = 30;
+    let (min, max) = region.bounds();
+    // with this cell size each cell contains at most one point
+    let mut grid = PointGrid::new(min, max, min_distance / 2f32.sqrt());
+    let Some(first) = random_point_in(region, rng) else {
+        return Vec::new();
+    };
+    grid.insert(first);
+    let mut points = vec![first];
+    let mut active = vec![first];
+    while !active.is_empty() {
+        let index = rng.gen_range(0..active.len());
+        let center = active[index];
+        let candidate = (0..CANDIDATES)
+            .map(|_| {
+                let angle = rng.gen::<f32>() * std::f32::consts::TAU;
+                let distance = min_distance * (1. + rng.gen::<f32>());
+                center + Vec2::new(angle.cos(), angle.sin()) * distance
+            })
+            .find(|candidate| {
+                region.contains(*candidate)
+                    && grid.closest_distance_squared(*candidate, 2) >= min_distance * min_distance
+            });
+        match candidate {
+            Some(candidate) => {
+                grid.insert(candidate);
+                points.push(candidate);
+                active.push(candidate);
+            }
+            None => {
+                active.swap_remove(index);
+            }
+        }
+    }
+    points
+}
+
+/// Blue noise sampling using Mitchell's best candidate algorithm
+fn blue_noise(region: &GrassRegion, count: usize, rng: &mut SmallRng) -> Vec<Vec2> {
+    // number of candidates compared for each point
+    const CANDIDATES: usize = 10;
+    let (min, max) = region.bounds();
+    let area = (max - min).x * (max - min).y;
+    // cells roughly the size of the average distance between points
+    let cell_size = (area / count.max(1) as f32).sqrt().max(f32::EPSILON);
+    let mut grid = PointGrid::new(min, max, cell_size);
+    let search_rings = 2;
+    let mut points = Vec::with_capacity(count);
+    for _ in 0..count {
+        let best = (0..CANDIDATES)
+            .filter_map(|_| random_point_in(region, rng))
+            .map(|candidate| {
+                (
+                    candidate,
+                    grid.closest_distance_squared(candidate, search_rings),
+                )
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        let Some((best, _)) = best else {
+            break;
+        };
+        grid.insert(best);
+        points.push(best);
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_same_seed_scatters_the_same_points_on_a_mesh() {
+        let mesh = Mesh::from(shape::Plane::from_size(4.));
+        let points = scatter_on_mesh(&mesh, 10., 7);
+        // 16 units of area with 10 points each
+        assert_eq!(points.len(), 160);
+        assert_eq!(points, scatter_on_mesh(&mesh, 10., 7));
+        assert_ne!(points, scatter_on_mesh(&mesh, 10., 8));
+        for point in points {
+            assert!(point.position.abs().cmple(Vec3::new(2., 0., 2.)).all());
+            assert_eq!(point.normal, Vec3::Y);
+        }
+    }
+
+    #[test]
+    fn the_same_seed_scatters_the_same_points_in_a_region() {
+        let region = GrassRegion::Circle {
+            center: Vec2::new(1., -2.),
+            radius: 3.,
+        };
+        let patterns = [
+            ScatterPattern::JitteredGrid {
+                spacing: 0.5,
+                jitter: 1.,
+            },
+            ScatterPattern::PoissonDisk { min_distance: 0.5 },
+            ScatterPattern::BlueNoise { count: 50 },
+        ];
+        for pattern in patterns {
+            let points = scatter_in_region(&region, pattern, 3);
+            assert!(!points.is_empty());
+            assert!(points.iter().all(|point| region.contains(*point)));
+            assert_eq!(points, scatter_in_region(&region, pattern, 3));
+            assert_ne!(points, scatter_in_region(&region, pattern, 4));
+        }
+    }
+
+    #[test]
+    fn poisson_disk_points_keep_their_distance() {
+        let region = GrassRegion::Rect {
+            min: Vec2::ZERO,
+            max: Vec2::splat(5.),
+        };
+        let min_distance = 0.3;
+        let points = scatter_in_region(&region, ScatterPattern::PoissonDisk { min_distance }, 0);
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                assert!(a.distance(*b) >= min_distance, "{a} and {b} are too close");
+            }
+        }
+        // the points fill the whole region, a grid with cells of the min distance would hold about 280 points
+        assert!(points.len() > 150, "only {} points", points.len());
+    }
+}
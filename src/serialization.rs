@@ -0,0 +1,208 @@
+//! Helpers to serialize types which don't implement `Serialize` and `Deserialize` themselves.
+//!
+//! Handles are not serialized, since their ids are only valid while the app is running.
+//! They are skipped, so the textures need to be set again after deserializing.
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::grass_spawner::{GrassSpawner, GrassSpawnerFlags};
+use crate::GrassConfiguration;
+
+/// The version of the format [`GrassSpawner`]s and [`GrassConfiguration`]s are serialized in.
+///
+/// Data serialized in another version is rejected instead of being misread.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct FormatVersion;
+impl FormatVersion {
+    const CURRENT: u32 = 1;
+}
+
+/// The serialized form of [`GrassSpawner`]s and [`GrassConfiguration`]s, which stores the format version before the data.
+///
+/// The types keep their fields, the version only exists in the serialized data.
+#[derive(Serialize, Deserialize)]
+struct Versioned<T> {
+    version: FormatVersion,
+    data: T,
+}
+
+/// A type with a derived `serde(remote = "Self")` implementation, which is serialized inside of [`Versioned`]
+trait VersionedData: Sized {
+    fn serialize_data<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+    fn deserialize_data<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+struct DataRef<'a, T>(&'a T);
+impl<T: VersionedData> Serialize for DataRef<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_data(serializer)
+    }
+}
+struct Data<T>(T);
+impl<'de, T: VersionedData> Deserialize<'de> for Data<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize_data(deserializer).map(Data)
+    }
+}
+
+macro_rules! impl_versioned {
+    ($type:ty) => {
+        impl VersionedData for $type {
+            fn serialize_data<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                // the inherent functions generated by `serde(remote = "Self")`
+                <$type>::serialize(self, serializer)
+            }
+            fn deserialize_data<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                <$type>::deserialize(deserializer)
+            }
+        }
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                Versioned {
+                    version: FormatVersion,
+                    data: DataRef(self),
+                }
+                .serialize(serializer)
+            }
+        }
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let versioned = Versioned::<Data<$type>>::deserialize(deserializer)?;
+                Ok(versioned.data.0)
+            }
+        }
+    };
+}
+impl_versioned!(GrassSpawner);
+impl_versioned!(GrassConfiguration);
+
+impl Serialize for GrassSpawnerFlags {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.bits().serialize(serializer)
+    }
+}
+impl<'de> Deserialize<'de> for GrassSpawnerFlags {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(GrassSpawnerFlags::from_bits_truncate)
+    }
+}
+
+impl Serialize for FormatVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FormatVersion::CURRENT.serialize(serializer)
+    }
+}
+impl<'de> Deserialize<'de> for FormatVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match u32::deserialize(deserializer)? {
+            FormatVersion::CURRENT => Ok(FormatVersion),
+            version => Err(D::Error::custom(format!(
+                "unsupported grass format version {version}, expected {}",
+                FormatVersion::CURRENT
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        coverage::{GrassCoverage, GrassCoverageMask},
+        grass_spawner::HeightRepresentation,
+        height_map::HeightMap,
+    };
+    use bevy::{asset::HandleId, prelude::*};
+
+    fn test_spawner() -> GrassSpawner {
+        GrassSpawner::new()
+            .with_positions_xz(vec![Vec2::new(0., 1.), Vec2::new(2., 3.)])
+            .with_heights(vec![1., 2.])
+            .with_height_map(HeightMap::new(
+                Handle::weak(HandleId::random::<Image>()),
+                4.,
+            ))
+            .with_colors(vec![Color::RED, Color::BLUE])
+    }
+    fn test_configuration() -> GrassConfiguration {
+        GrassConfiguration {
+            main_color: Color::RED,
+            bottom_color: Color::BLACK,
+            wind: Vec2::new(1., 2.),
+            wind_noise_texture: Handle::weak(HandleId::random::<Image>()),
+            coverage: GrassCoverage::snow(0.5),
+            coverage_mask: Some(GrassCoverageMask::new(
+                Handle::weak(HandleId::random::<Image>()),
+                Vec2::new(1., 2.),
+                Vec2::splat(50.),
+            )),
+        }
+    }
+    fn assert_spawners_eq(decoded: &GrassSpawner, original: &GrassSpawner) {
+        assert_eq!(decoded.flags, original.flags);
+        assert_eq!(decoded.positions_xz, original.positions_xz);
+        assert_eq!(decoded.colors, original.colors);
+        let (
+            HeightRepresentation::PerBlade(decoded_heights),
+            HeightRepresentation::PerBlade(heights),
+        ) = (&decoded.heights, &original.heights)
+        else {
+            panic!("the heights should be stored per blade");
+        };
+        assert_eq!(decoded_heights, heights);
+        let height_map = decoded.height_map.as_ref().unwrap();
+        assert_eq!(height_map.height, 4.);
+        // the handle has to be set again by the caller
+        assert_eq!(height_map.height_map, Handle::default());
+    }
+    fn assert_configurations_eq(decoded: &GrassConfiguration, original: &GrassConfiguration) {
+        assert_eq!(decoded.main_color, original.main_color);
+        assert_eq!(decoded.bottom_color, original.bottom_color);
+        assert_eq!(decoded.wind, original.wind);
+        assert_eq!(decoded.coverage, original.coverage);
+        assert_eq!(decoded.wind_noise_texture, Handle::default());
+        let (decoded_mask, mask) = (
+            decoded.coverage_mask.as_ref().unwrap(),
+            original.coverage_mask.as_ref().unwrap(),
+        );
+        assert_eq!(
+            (decoded_mask.origin, decoded_mask.size),
+            (mask.origin, mask.size)
+        );
+        assert_eq!(decoded_mask.texture, Handle::default());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let spawner = test_spawner();
+        let json = serde_json::to_string(&spawner).unwrap();
+        assert_spawners_eq(&serde_json::from_str(&json).unwrap(), &spawner);
+
+        let configuration = test_configuration();
+        let json = serde_json::to_string(&configuration).unwrap();
+        assert_configurations_eq(&serde_json::from_str(&json).unwrap(), &configuration);
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let spawner = test_spawner();
+        let bytes = bincode::serialize(&spawner).unwrap();
+        assert_spawners_eq(&bincode::deserialize(&bytes).unwrap(), &spawner);
+
+        let configuration = test_configuration();
+        let bytes = bincode::serialize(&configuration).unwrap();
+        assert_configurations_eq(&bincode::deserialize(&bytes).unwrap(), &configuration);
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let mut json = serde_json::to_value(test_spawner()).unwrap();
+        assert_eq!(json["version"], FormatVersion::CURRENT);
+        json["version"] = (FormatVersion::CURRENT + 1).into();
+        assert!(serde_json::from_value::<GrassSpawner>(json).is_err());
+
+        let mut bytes = bincode::serialize(&test_configuration()).unwrap();
+        bytes[0] += 1;
+        assert!(bincode::deserialize::<GrassConfiguration>(&bytes).is_err());
+    }
+}
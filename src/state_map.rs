@@ -0,0 +1,206 @@
+use bevy::{
+    prelude::*,
+    render::{
+        extract_resource::ExtractResource,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use crate::trail_map::rgb8_texel_layout;
+
+/// A state the grass can be in, stored in a channel of the [`GrassStateMap`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, FromReflect)]
+pub enum GrassState {
+    /// Charred and shortened blades, for example after a fire
+    Burnt,
+    /// Blades with white tips
+    Frozen,
+    /// Darker blades with glossy tips, for example after rain
+    Wet,
+}
+impl GrassState {
+    /// The channel of the state map texture storing the state
+    fn channel(self) -> usize {
+        match self {
+            GrassState::Burnt => 0,
+            GrassState::Frozen => 1,
+            GrassState::Wet => 2,
+        }
+    }
+}
+
+/// A [resource](bevy::prelude::Resource) containing a world space texture which stores the [`GrassState`]s of the grass.
+///
+/// Each state has an amount in the range `[0, 1]`, which can be painted into regions using
+/// [`GrassStateMap::paint_circle`] and [`GrassStateMap::paint_rect`].
+/// States don't change on their own, so burnt grass stays burnt until the state is painted again.
+/// The grass shader reads the texture to apply the effect of each state.
+///
+/// The state map is optional and only used if the resource is inserted, for example using [`GrassStateMap::new`].
+/// Initializing the resource with `app.init_resource::<GrassStateMap>()` covers the area from `(0, 0)` to `(100, 100)`.
+#[derive(Resource, Clone, Reflect, ExtractResource)]
+#[reflect(Resource)]
+pub struct GrassStateMap {
+    /// The texture storing the states.
+    ///
+    /// The red channel stores how burnt, the green channel how frozen and the blue channel how wet the grass is.
+    /// The texture needs at least three channels with 8 bits each, like the `Rgba8Unorm` texture created by [`GrassStateMap::new`].
+    pub texture: Handle<Image>,
+    /// The world position of the corner of the state map with the lowest x and z coordinates.
+    pub origin: Vec2,
+    /// The size of the area covered by the state map on the x,z plane.
+    pub size: Vec2,
+    /// The color of fully burnt blades
+    pub burnt_color: Color,
+    /// How much fully burnt blades are shortened. Should be in the range `[0, 1]`.
+    pub burnt_shrink: f32,
+    /// The color of the tips of fully frozen blades
+    pub frost_color: Color,
+    /// How much fully wet blades are darkened. Should be in the range `[0, 1]`.
+    pub wet_darken: f32,
+    /// How much the tips of fully wet blades are brightened, like a reflection. Should be in the range `[0, 1]`.
+    pub wet_gloss: f32,
+}
+
+impl GrassStateMap {
+    /// Creates a new state map without any states, covering the given area with a texture of the given resolution.
+    pub fn new(images: &mut Assets<Image>, origin: Vec2, size: Vec2, resolution: UVec2) -> Self {
+        assert!(resolution.x > 0 && resolution.y > 0);
+        let image = Image::new_fill(
+            Extent3d {
+                width: resolution.x,
+                height: resolution.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8Unorm,
+        );
+        GrassStateMap {
+            texture: images.add(image),
+            origin,
+            size,
+            burnt_color: Color::rgb(0.08, 0.06, 0.04),
+            burnt_shrink: 0.6,
+            frost_color: Color::rgb(0.9, 0.95, 1.),
+            wet_darken: 0.4,
+            wet_gloss: 0.3,
+        }
+    }
+    /// Sets the amount of the state in a circle on the x,z plane.
+    ///
+    /// An amount of zero removes the state.
+    pub fn paint_circle(
+        &self,
+        images: &mut Assets<Image>,
+        state: GrassState,
+        center: Vec2,
+        radius: f32,
+        amount: f32,
+    ) {
+        self.paint(
+            images,
+            state,
+            center - radius,
+            center + radius,
+            amount,
+            |p| p.distance(center) <= radius,
+        );
+    }
+    /// Sets the amount of the state in the rectangle between `min` and `max` on the x,z plane.
+    ///
+    /// An amount of zero removes the state.
+    pub fn paint_rect(
+        &self,
+        images: &mut Assets<Image>,
+        state: GrassState,
+        min: Vec2,
+        max: Vec2,
+        amount: f32,
+    ) {
+        self.paint(images, state, min, max, amount, |p| {
+            p.cmpge(min).all() && p.cmple(max).all()
+        });
+    }
+    /// Removes the state from the whole state map
+    pub fn clear(&self, images: &mut Assets<Image>, state: GrassState) {
+        let Some(image) = images.get_mut(&self.texture) else {
+            return;
+        };
+        let (stride, channel) = texel_layout(image, state);
+        for texel in image.data.chunks_exact_mut(stride) {
+            texel[channel] = 0;
+        }
+    }
+    /// Returns the amount of the state at the position on the x,z plane.
+    ///
+    /// Returns `None` if the position is outside of the state map or the texture isn't loaded.
+    pub fn state_at(
+        &self,
+        images: &Assets<Image>,
+        state: GrassState,
+        position: Vec2,
+    ) -> Option<f32> {
+        let image = images.get(&self.texture)?;
+        let resolution = image.size().as_uvec2();
+        let uv = (position - self.origin) / self.size;
+        if uv.cmplt(Vec2::ZERO).any() || uv.cmpge(Vec2::ONE).any() {
+            return None;
+        }
+        let (stride, channel) = texel_layout(image, state);
+        let texel = (uv * resolution.as_vec2()).as_uvec2();
+        let index = (texel.y * resolution.x + texel.x) as usize * stride;
+        Some(image.data[index + channel] as f32 / 255.)
+    }
+    /// Sets the state of all texels between `min` and `max` whose center is inside the shape
+    fn paint(
+        &self,
+        images: &mut Assets<Image>,
+        state: GrassState,
+        min: Vec2,
+        max: Vec2,
+        amount: f32,
+        inside: impl Fn(Vec2) -> bool,
+    ) {
+        let Some(image) = images.get_mut(&self.texture) else {
+            return;
+        };
+        let resolution = image.size().as_uvec2();
+        let texel_size = self.size / resolution.as_vec2();
+        let min = ((min - self.origin) / texel_size).floor();
+        let max = ((max - self.origin) / texel_size).ceil();
+        let min = min.max(Vec2::ZERO).as_uvec2();
+        let max = max.min(resolution.as_vec2()).as_uvec2();
+        let value = (amount.clamp(0., 1.) * 255.) as u8;
+        let (stride, channel) = texel_layout(image, state);
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let texel_center = self.origin + (UVec2::new(x, y).as_vec2() + 0.5) * texel_size;
+                if inside(texel_center) {
+                    let index = (y * resolution.x + x) as usize * stride;
+                    image.data[index + channel] = value;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the bytes per texel of the state map texture and the byte storing the state.
+///
+/// Panics if the texture doesn't store the states in 8-bit channels
+fn texel_layout(image: &Image, state: GrassState) -> (usize, usize) {
+    let (stride, channels) = rgb8_texel_layout(image, "state map");
+    (stride, channels[state.channel()])
+}
+
+impl FromWorld for GrassStateMap {
+    fn from_world(world: &mut World) -> Self {
+        let mut images = world.resource_mut::<Assets<Image>>();
+        GrassStateMap::new(
+            &mut images,
+            Vec2::ZERO,
+            Vec2::splat(100.),
+            UVec2::splat(256),
+        )
+    }
+}
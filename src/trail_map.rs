@@ -0,0 +1,228 @@
+use bevy::{
+    math::Vec3Swizzles,
+    prelude::*,
+    render::{
+        extract_resource::ExtractResource,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+/// A [resource](bevy::prelude::Resource) containing a world space texture which stores how much the grass is trampled.
+///
+/// [`GrassDisplacer`]s stamp into the texture each frame and the trampling recovers over time,
+/// so footpaths slowly form where entities walk often.
+/// The grass shader reads the texture to bend the blades to the ground and darken them.
+///
+/// The trail map is optional and only used if the resource is inserted, for example using [`GrassTrailMap::new`].
+/// Initializing the resource with `app.init_resource::<GrassTrailMap>()` covers the area from `(0, 0)` to `(100, 100)`.
+#[derive(Resource, Clone, Reflect, ExtractResource)]
+#[reflect(Resource)]
+pub struct GrassTrailMap {
+    /// The texture storing the trampling.
+    ///
+    /// The red channel stores the amount of trampling,
+    /// the green and blue channel store the direction in which the blades are pressed on the x,z plane.
+    /// The texture needs at least three channels with 8 bits each, like the `Rgba8Unorm` texture created by [`GrassTrailMap::new`].
+    pub texture: Handle<Image>,
+    /// The world position of the corner of the trail map with the lowest x and z coordinates.
+    pub origin: Vec2,
+    /// The size of the area covered by the trail map on the x,z plane.
+    pub size: Vec2,
+    /// How much trampling recovers each second.
+    ///
+    /// A value of `0.1` means that fully trampled grass needs ten seconds to stand up again.
+    pub recovery_rate: f32,
+    /// How far fully trampled blades are pressed to the ground. Should be in the range `[0, 1]`.
+    pub flatten: f32,
+    /// How much fully trampled blades are darkened. Should be in the range `[0, 1]`.
+    pub darken: f32,
+    /// Recovery which wasn't applied yet, since it is smaller than the precision of the texture.
+    #[reflect(ignore)]
+    pending_recovery: f32,
+    /// Whether any texel may still be trampled, so the recovery can be skipped otherwise.
+    #[reflect(ignore)]
+    trampled: bool,
+}
+
+impl GrassTrailMap {
+    /// Creates a new empty trail map covering the given area with a texture of the given resolution.
+    pub fn new(images: &mut Assets<Image>, origin: Vec2, size: Vec2, resolution: UVec2) -> Self {
+        assert!(resolution.x > 0 && resolution.y > 0);
+        let image = Image::new_fill(
+            Extent3d {
+                width: resolution.x,
+                height: resolution.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 127, 127, 255],
+            TextureFormat::Rgba8Unorm,
+        );
+        GrassTrailMap {
+            texture: images.add(image),
+            origin,
+            size,
+            recovery_rate: 0.05,
+            flatten: 0.8,
+            darken: 0.3,
+            pending_recovery: 0.,
+            // a texture set later may already be trampled, it's checked by the first recovery
+            trampled: true,
+        }
+    }
+}
+
+impl FromWorld for GrassTrailMap {
+    fn from_world(world: &mut World) -> Self {
+        let mut images = world.resource_mut::<Assets<Image>>();
+        GrassTrailMap::new(
+            &mut images,
+            Vec2::ZERO,
+            Vec2::splat(100.),
+            UVec2::splat(256),
+        )
+    }
+}
+
+/// A component for entities which trample the grass they walk over.
+///
+/// Only has an effect if a [`GrassTrailMap`] resource exists.
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct GrassDisplacer {
+    /// The radius around the entity in which grass gets trampled.
+    pub radius: f32,
+    /// How much the grass in the center is trampled. Should be in the range `[0, 1]`.
+    ///
+    /// The trampling doesn't add up over multiple frames,
+    /// the grass below the displacer is trampled to at least this amount.
+    pub strength: f32,
+}
+impl Default for GrassDisplacer {
+    fn default() -> Self {
+        GrassDisplacer {
+            radius: 0.5,
+            strength: 1.,
+        }
+    }
+}
+
+/// Lets the trampling recover and stamps all [`GrassDisplacer`]s into the [`GrassTrailMap`].
+///
+/// The texture is only modified, and therefore uploaded again, if a texel changes.
+pub(crate) fn update_trail_map(
+    time: Res<Time>,
+    mut trail_map: ResMut<GrassTrailMap>,
+    mut images: ResMut<Assets<Image>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut modified_texture: Local<bool>,
+    displacers: Query<(&GlobalTransform, &GrassDisplacer)>,
+) {
+    // a new texture or a texture modified elsewhere may contain trampled texels, which need to recover
+    let mut own_modification = std::mem::take(&mut *modified_texture);
+    let mut texture_changed = false;
+    for event in image_events.iter() {
+        match event {
+            AssetEvent::Created { handle } if *handle == trail_map.texture => {
+                texture_changed = true;
+            }
+            AssetEvent::Modified { handle } if *handle == trail_map.texture => {
+                texture_changed |= !std::mem::take(&mut own_modification);
+            }
+            _ => {}
+        }
+    }
+    let trail_map_changed = trail_map.is_changed();
+    let Some(image) = images.get(&trail_map.texture) else {
+        return;
+    };
+    let trail_map = trail_map.bypass_change_detection();
+    if texture_changed || trail_map_changed {
+        trail_map.trampled = true;
+    }
+    let (stride, [red, green, blue]) = rgb8_texel_layout(image, "trail map");
+    // the recovery is applied in steps of the texture precision
+    let pending = if trail_map.trampled {
+        trail_map.pending_recovery + time.delta_seconds() * trail_map.recovery_rate
+    } else {
+        0.
+    };
+    let recovery_steps = (pending * 255.).floor();
+    trail_map.pending_recovery = pending - recovery_steps / 255.;
+    let recovery_steps = recovery_steps.min(255.) as u8;
+
+    // the stamps are collected first, so the texture isn't modified if nothing changes
+    let resolution = image.size().as_uvec2();
+    let texel_size = trail_map.size / resolution.as_vec2();
+    let mut stamps = Vec::new();
+    for (transform, displacer) in displacers.iter() {
+        let center = transform.translation().xz();
+        let min = ((center - displacer.radius - trail_map.origin) / texel_size).floor();
+        let max = ((center + displacer.radius - trail_map.origin) / texel_size).ceil();
+        let min = min.max(Vec2::ZERO).as_uvec2();
+        let max = max.min(resolution.as_vec2()).as_uvec2();
+        for y in min.y..max.y {
+            for x in min.x..max.x {
+                let texel_center =
+                    trail_map.origin + (UVec2::new(x, y).as_vec2() + 0.5) * texel_size;
+                let offset = texel_center - center;
+                let distance = offset.length();
+                if distance > displacer.radius {
+                    continue;
+                }
+                let amount = displacer.strength.clamp(0., 1.) * (1. - distance / displacer.radius);
+                let amount = (amount * 255.) as u8;
+                let index = (y * resolution.x + x) as usize * stride;
+                if amount > image.data[index + red].saturating_sub(recovery_steps) {
+                    let direction = offset.normalize_or_zero() * 0.5 + 0.5;
+                    stamps.push((index, amount, direction));
+                }
+            }
+        }
+    }
+    if recovery_steps == 0 && stamps.is_empty() {
+        return;
+    }
+
+    let image = images.get_mut(&trail_map.texture).unwrap();
+    *modified_texture = true;
+    if recovery_steps > 0 {
+        let mut trampled = false;
+        for texel in image.data.chunks_exact_mut(stride) {
+            texel[red] = texel[red].saturating_sub(recovery_steps);
+            trampled |= texel[red] > 0;
+        }
+        trail_map.trampled = trampled;
+    }
+    if !stamps.is_empty() {
+        trail_map.trampled = true;
+    }
+    for (index, amount, direction) in stamps {
+        let texel = &mut image.data[index..index + stride];
+        if amount > texel[red] {
+            texel[red] = amount;
+            texel[green] = (direction.x * 255.) as u8;
+            texel[blue] = (direction.y * 255.) as u8;
+        }
+    }
+}
+
+/// Returns the bytes per texel of a texture with 8-bit channels and the bytes storing the red, green and blue channel.
+///
+/// Panics if the texture doesn't have at least three 8-bit channels, the `name` of the texture is used in the message
+pub(crate) fn rgb8_texel_layout(image: &Image, name: &str) -> (usize, [usize; 3]) {
+    let format = image.texture_descriptor.format;
+    let info = format.describe();
+    assert!(
+        info.block_dimensions == (1, 1)
+            && info.block_size == info.components
+            && info.components >= 3,
+        "The {name} texture needs at least three 8-bit channels, but has the format {format:?}"
+    );
+    // bgra textures are swizzled when read by the shader
+    let channels = match format {
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => [2, 1, 0],
+        _ => [0, 1, 2],
+    };
+    (info.block_size as usize, channels)
+}
@@ -6,15 +6,27 @@ use bevy::{
     render::{
         extract_resource::ExtractResourcePlugin,
         mesh::Indices,
+        render_asset::PrepareAssetSet,
         render_phase::AddRenderCommand,
         render_resource::{PrimitiveTopology, SpecializedMeshPipelines},
         texture::FallbackImage,
         RenderApp, RenderSet,
     },
+    time::TimeSystem,
 };
 
 use crate::{
-    grass_spawner::add_aabb_box_to_grass,
+    clock::{self, GrassClock, GrassClockSource},
+    coverage::{GrassCoverage, GrassCoverageMask},
+    diagnostic::{self, GrassRenderStats},
+    grass::GrassBlade,
+    grass_asset::{self, GrassChunkAsset, GrassChunkLoader},
+    grass_spawner::{
+        add_aabb_box_to_grass, clear_grass_updates, regrow_grass, GrassRegrowth, GrassSpawner,
+        GrassSpawnerFlags, HeightRepresentation,
+    },
+    growth::{self, GrassGrowth, GrowthEasing},
+    height_map::{self, HeightMap, HeightMapChannel, HeightMapExtent, HeightMapFilter},
     hot_reloading,
     render::{
         self,
@@ -23,6 +35,9 @@ use crate::{
         grass_pipeline::GrassPipeline,
         prepare, queue,
     },
+    state_map::{GrassState, GrassStateMap},
+    trail_map::{self, GrassDisplacer, GrassTrailMap},
+    wind::{self, GRASS_WIND_TEXTURE_HANDLE},
     GrassConfiguration,
 };
 
@@ -30,6 +45,16 @@ use crate::{
 pub(crate) const GRASS_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 2263343952151597127);
 
+/// A raw handle to the shader module `warbler_grass::grass_types`,
+/// which defines the output of the grass vertex shader.
+pub(crate) const GRASS_TYPES_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 7480276339810154371);
+
+/// A raw handle to the shader module `warbler_grass::wind`,
+/// which calculates the wind of the grass.
+pub(crate) const WIND_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1849067325548110627);
+
 /// A raw handle to the default mesh used for grass.
 ///
 /// The [`WarblersPlugin`] adds the corresponding mesh to the world.
@@ -48,20 +73,85 @@ impl Plugin for WarblersPlugin {
             "render/assets/grass_shader.wgsl",
             Shader::from_wgsl
         );
+        load_internal_asset!(
+            app,
+            GRASS_TYPES_SHADER_HANDLE,
+            "render/assets/grass_types.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            WIND_SHADER_HANDLE,
+            "render/assets/wind.wgsl",
+            Shader::from_wgsl
+        );
 
         // Load default grass blade mesh
         let mut meshes = app.world.resource_mut::<Assets<Mesh>>();
         meshes.set_untracked(GRASS_MESH_HANDLE, default_grass_mesh());
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        images.set_untracked(GRASS_WIND_TEXTURE_HANDLE, wind::wind_texture());
         // Add systems
         app.add_system(add_aabb_box_to_grass);
+        app.add_system(regrow_grass);
+        app.add_system(growth::update_grass_growth);
+        app.add_system(clear_grass_updates.in_base_set(CoreSet::First));
         app.add_system(hot_reloading::hot_reload_height_map);
+        app.add_system(hot_reloading::hot_reload_grass_chunks);
+        app.add_system(grass_asset::insert_grass_from_assets);
+        app.add_system(height_map::convert_height_map_formats);
+        app.add_system(trail_map::update_trail_map.run_if(resource_exists::<GrassTrailMap>()));
+        app.add_system(
+            clock::update_grass_clock
+                .in_base_set(CoreSet::First)
+                .after(TimeSystem),
+        );
+        // Add assets
+        app.add_asset::<GrassChunkAsset>()
+            .init_asset_loader::<GrassChunkLoader>();
         // Init resources
         app.init_resource::<GrassConfiguration>()
-            .register_type::<GrassConfiguration>();
+            .init_resource::<GrassClock>()
+            .register_type::<GrassConfiguration>()
+            .register_type::<GrassClock>()
+            .register_type::<GrassClockSource>()
+            .register_type::<GrassCoverage>()
+            .register_type::<GrassCoverageMask>()
+            .register_type::<Option<GrassCoverageMask>>()
+            .register_type::<GrassTrailMap>()
+            .register_type::<GrassDisplacer>()
+            .register_type::<GrassStateMap>()
+            .register_type::<GrassState>()
+            .register_type::<GrassRegrowth>()
+            .register_type::<GrassGrowth>()
+            .register_type::<GrowthEasing>()
+            .register_type::<GrassSpawner>()
+            .register_type::<GrassSpawnerFlags>()
+            .register_type::<HeightRepresentation>()
+            .register_type::<GrassBlade>()
+            .register_type::<HeightMap>()
+            .register_type::<HeightMapExtent>()
+            .register_type::<HeightMapChannel>()
+            .register_type::<HeightMapFilter>()
+            // generic field types of the grass components, needed to load them from scenes
+            .register_type::<Vec<Vec2>>()
+            .register_type::<Vec<Vec4>>()
+            .register_type::<Vec<f32>>()
+            .register_type::<Option<Vec<f32>>>()
+            .register_type::<Option<HeightMap>>()
+            .register_type::<Option<HeightMapExtent>>()
+            .register_type::<Option<Handle<Image>>>();
         // Add extraction
         app.add_plugin(ExtractResourcePlugin::<GrassConfiguration>::default());
+        app.add_plugin(ExtractResourcePlugin::<GrassTrailMap>::default());
+        app.add_plugin(ExtractResourcePlugin::<GrassStateMap>::default());
+        app.add_plugin(ExtractResourcePlugin::<GrassClock>::default());
+        // the statistics are shared between the main and render world
+        let stats = GrassRenderStats::default();
+        app.insert_resource(stats.clone());
         // Init render app
         app.sub_app_mut(RenderApp)
+            .insert_resource(stats)
             .add_render_command::<Opaque3d, render::GrassDrawCall>()
             .init_resource::<FallbackImage>()
             .init_resource::<GrassPipeline>()
@@ -69,14 +159,42 @@ impl Plugin for WarblersPlugin {
             .init_resource::<EntityCache>()
             .init_resource::<SpecializedMeshPipelines<GrassPipeline>>()
             .add_systems(
-                (extract::extract_grass, extract::extract_visibility).in_schedule(ExtractSchedule),
+                (
+                    extract::extract_removed_grass.before(extract::extract_grass),
+                    extract::extract_grass,
+                    extract::extract_grass_growth.after(extract::extract_grass),
+                    extract::extract_grass_coverage.after(extract::extract_grass),
+                    extract::extract_visibility,
+                )
+                    .in_schedule(ExtractSchedule),
             )
             .add_system(prepare::prepare_uniform_buffers.in_set(RenderSet::Prepare))
+            .add_system(
+                prepare::prepare_wind_texture
+                    .in_set(RenderSet::Prepare)
+                    .after(PrepareAssetSet::AssetPrepare),
+            )
             .add_system(prepare::prepare_explicit_xz_buffer.in_set(RenderSet::Prepare))
             .add_system(prepare::prepare_explicit_y_buffer.in_set(RenderSet::Prepare))
             .add_system(prepare::prepare_height_buffer.in_set(RenderSet::Prepare))
+            .add_system(prepare::prepare_height_updates.in_set(RenderSet::Prepare))
+            .add_system(
+                prepare::prepare_coverage_updates
+                    .in_set(RenderSet::Prepare)
+                    .after(prepare::prepare_height_buffer),
+            )
+            .add_system(
+                prepare::prepare_growth_updates
+                    .in_set(RenderSet::Prepare)
+                    .after(prepare::prepare_height_buffer),
+            )
             .add_system(prepare::prepare_height_map_buffer.in_set(RenderSet::Prepare))
-            .add_system(queue::queue_grass_buffers.in_set(RenderSet::Queue));
+            .add_system(queue::queue_grass_buffers.in_set(RenderSet::Queue))
+            .add_system(
+                diagnostic::update_grass_stats
+                    .in_set(RenderSet::Queue)
+                    .after(queue::queue_grass_buffers),
+            );
     }
 }
 
@@ -0,0 +1,244 @@
+//! Lets other materials sway in the same wind as the grass.
+//!
+//! The wind of the grass is calculated by the shader module `warbler_grass::wind`,
+//! which can be imported by any shader using `#import warbler_grass::wind`.
+//! It contains the function `load_grass_wind`, which reads the wind from the [`GRASS_WIND_TEXTURE_HANDLE`],
+//! and the functions `wind_offset` and `wind_strength`:
+//! ```wgsl
+//! #import warbler_grass::wind
+//!
+//! @group(1) @binding(0)
+//! var wind_texture: texture_2d<f32>;
+//! @group(1) @binding(1)
+//! var wind_noise: texture_2d<f32>;
+//!
+//! // in the vertex shader, `height` is the height of the vertex above the ground of the object
+//! let grass_wind = load_grass_wind(wind_texture);
+//! let offset = wind_offset(wind_noise, grass_wind.wind, grass_wind.noise_offset, world_position.xz);
+//! world_position.x += offset.x * wind_strength(height);
+//! world_position.z += offset.y * wind_strength(height);
+//! ```
+//! The textures are set by the [`GrassWindPlugin`].
+//!
+//! The same wind can be calculated on the CPU using [`wind_displacement`],
+//! for example to move particles along with the grass.
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::Asset,
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use crate::{clock::GrassClock, height_map::texel_value, GrassConfiguration};
+
+/// How fast the wind moves over the noise texture
+const WIND_NOISE_SPEED: f32 = 30.;
+/// How many texels of the noise texture are covered by one unit on the x,z plane
+const WIND_NOISE_ZOOM: f32 = 5.;
+
+/// A raw handle to the texture storing the current wind of the grass.
+///
+/// The texture has two `Rgba32Float` texels.
+/// The red and green channels of the first texel contain the [`GrassConfiguration::wind`],
+/// its blue and alpha channels contain the [`wind_noise_offset`].
+/// The red channel of the second texel contains the [`GrassClock::elapsed`] time.
+/// The texels are written on the GPU whenever the wind changes, so materials binding the texture don't need to be prepared again.
+/// It can be read using the `load_grass_wind` function of the `warbler_grass::wind` shader module.
+///
+/// The texture is added by the [`WarblersPlugin`](crate::warblers_plugin::WarblersPlugin).
+pub const GRASS_WIND_TEXTURE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Image::TYPE_UUID, 5128907461230846217);
+
+/// Creates the texture behind the [`GRASS_WIND_TEXTURE_HANDLE`]
+pub(crate) fn wind_texture() -> Image {
+    Image::new_fill(
+        Extent3d {
+            width: 2,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        bytemuck::cast_slice(&[0f32; 4]),
+        TextureFormat::Rgba32Float,
+    )
+}
+
+/// A material whose bindings contain the wind of the grass.
+///
+/// Normally the material stores both textures as `#[texture]` bindings,
+/// the wind texture using `sample_type = "float", filterable = false`
+pub trait GrassWindMaterial: Asset {
+    /// Sets the textures the wind is read from, see [`GRASS_WIND_TEXTURE_HANDLE`]
+    fn set_grass_wind(&mut self, wind_texture: &Handle<Image>, noise_texture: &Handle<Image>);
+}
+
+/// Lets all materials of the type `M` sway in the same wind as the grass.
+///
+/// The textures of a material are set when it is created or the [`GrassConfiguration::wind_noise_texture`] changes.
+/// All materials read the wind from the same texture, so changes of the wind don't prepare the materials again.
+/// The [`WarblersPlugin`](crate::warblers_plugin::WarblersPlugin) needs to be added as well.
+pub struct GrassWindPlugin<M: GrassWindMaterial>(PhantomData<M>);
+
+impl<M: GrassWindMaterial> Default for GrassWindPlugin<M> {
+    fn default() -> Self {
+        GrassWindPlugin(PhantomData)
+    }
+}
+
+impl<M: GrassWindMaterial> Plugin for GrassWindPlugin<M> {
+    fn build(&self, app: &mut App) {
+        app.add_system(update_wind_materials::<M>);
+    }
+}
+
+fn update_wind_materials<M: GrassWindMaterial>(
+    config: Res<GrassConfiguration>,
+    mut ev_asset: EventReader<AssetEvent<M>>,
+    mut materials: ResMut<Assets<M>>,
+    mut last_noise_texture: Local<Option<Handle<Image>>>,
+) {
+    let wind_texture = GRASS_WIND_TEXTURE_HANDLE.typed();
+    if last_noise_texture.as_ref() != Some(&config.wind_noise_texture) {
+        *last_noise_texture = Some(config.wind_noise_texture.clone());
+        ev_asset.clear();
+        for (_, material) in materials.iter_mut() {
+            material.set_grass_wind(&wind_texture, &config.wind_noise_texture);
+        }
+        return;
+    }
+    for ev in ev_asset.iter() {
+        if let AssetEvent::Created { handle } = ev {
+            if let Some(material) = materials.get_mut(handle) {
+                material.set_grass_wind(&wind_texture, &config.wind_noise_texture);
+            }
+        }
+    }
+}
+
+/// Returns how far the wind moved over the noise texture, in texels.
+///
+/// The offset is calculated from the [`GrassClock::elapsed_seconds`] with full precision
+/// and wrapped into the size of the noise texture.
+/// The noise texture tiles, so the wind never jumps, however long the clock runs.
+pub fn wind_noise_offset(wind: Vec2, clock: &GrassClock, noise_size: Vec2) -> Vec2 {
+    let offset = wind.as_dvec2() * clock.elapsed_seconds() * WIND_NOISE_SPEED as f64;
+    let noise_size = noise_size.as_dvec2();
+    Vec2::new(
+        offset.x.rem_euclid(noise_size.x) as f32,
+        offset.y.rem_euclid(noise_size.y) as f32,
+    )
+}
+
+/// Returns the offset on the x,z plane caused by the wind at the world position.
+///
+/// This is the same calculation as the `wind_offset` function of the `warbler_grass::wind` shader module.
+/// The `noise_texture` should be the [`GrassConfiguration::wind_noise_texture`]
+/// and the `clock` should be the [`GrassClock`] used by the grass.
+///
+/// Returns `None` if the format of the noise texture is not supported.
+pub fn wind_offset(
+    config: &GrassConfiguration,
+    noise_texture: &Image,
+    clock: &GrassClock,
+    position: Vec2,
+) -> Option<Vec2> {
+    let dim = noise_texture.size();
+    let texture_position = position * WIND_NOISE_ZOOM + wind_noise_offset(config.wind, clock, dim);
+    // the noise texture tiles, positions outside of it are wrapped into it
+    let wrapped = texture_position - (texture_position / dim).floor() * dim;
+    let texel = wrapped.as_uvec2().min(dim.as_uvec2() - 1);
+    let noise = Vec2::new(
+        texel_value(noise_texture, texel, 0)?,
+        texel_value(noise_texture, texel, 1)?,
+    );
+    Some(noise * config.wind)
+}
+
+/// Returns how much a vertex at the height is moved by the wind.
+///
+/// The bottom of a blade (height 0) doesn't move at all
+pub fn wind_strength(height: f32) -> f32 {
+    (height + 1.).ln().max(0.)
+}
+
+/// Returns how far the wind moves a point of a blade on the x,z plane, exactly like the shader does.
+///
+/// The `position` is the world position of the root of the blade on the x,z plane
+/// and the `height` is the height of the point on the blade mesh, which is 1 at the tip of the default grass mesh.
+/// The displacement is in world space, regardless of the [`Transform`] of the grass chunk.
+/// See [`wind_offset`] for the other parameters.
+pub fn wind_displacement(
+    config: &GrassConfiguration,
+    noise_texture: &Image,
+    clock: &GrassClock,
+    position: Vec2,
+    height: f32,
+) -> Option<Vec2> {
+    Some(wind_offset(config, noise_texture, clock, position)? * wind_strength(height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::GrassClockSource;
+
+    /// A 4x4 noise texture, whose texels all have different values which aren't zero
+    fn noise_texture() -> Image {
+        let data: Vec<u8> = (1..=16u8)
+            .flat_map(|i| [i * 15, 255 - i * 15, 0, 255])
+            .collect();
+        Image::new(
+            Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8Unorm,
+        )
+    }
+    fn clock_at(seconds: f64) -> GrassClock {
+        let mut clock = GrassClock::new(GrassClockSource::Manual);
+        clock.set_elapsed(seconds);
+        clock
+    }
+
+    #[test]
+    fn wind_is_continuous_over_long_times() {
+        let noise = noise_texture();
+        let config = GrassConfiguration {
+            main_color: Color::WHITE,
+            bottom_color: Color::WHITE,
+            // the wind moves 7.5 texels per second on the x axis, the value is exact in floats
+            wind: Vec2::new(0.25, 0.),
+            wind_noise_texture: Handle::default(),
+            coverage: default(),
+            coverage_mask: None,
+        };
+        // the position lies in the center of a texel, so the wind only moves it by a fraction of a texel
+        let position = Vec2::new(0.3, 0.1);
+        for seconds in [3600., 7200., 1e6, 1e8] {
+            let before = wind_offset(&config, &noise, &clock_at(seconds - 0.01), position);
+            let after = wind_offset(&config, &noise, &clock_at(seconds + 0.01), position);
+            assert_eq!(before, after, "the wind jumped after {seconds} seconds");
+        }
+        // the wind moves over the noise texture with the time
+        let moved = wind_offset(&config, &noise, &clock_at(1e8 + 0.25), position);
+        assert_ne!(
+            moved,
+            wind_offset(&config, &noise, &clock_at(1e8), position)
+        );
+    }
+
+    #[test]
+    fn noise_offset_wraps_into_the_texture() {
+        let size = Vec2::splat(4.);
+        for seconds in [0., 0.5, 3600., 1e9] {
+            let offset = wind_noise_offset(Vec2::new(0.7, -0.3), &clock_at(seconds), size);
+            assert!(offset.cmpge(Vec2::ZERO).all() && offset.cmple(size).all());
+        }
+    }
+}